@@ -1,77 +1,565 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+use anchor_lang::Discriminator;
+use anchor_spl::token::TokenAccount;
 
 declare_id!("7a6vstpjcuYDJDGiyvhkTCteZePCwpwDzucLCe2uacmY");
 
+// Hard limits used to size accounts, Config can only tighten them
+pub const MAX_CONTENT_LENGTH: usize = 280;
+pub const MAX_USERNAME_LENGTH: usize = 50;
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+pub const MAX_BIO_LENGTH: usize = 160;
+pub const MAX_URL_LENGTH: usize = 200;
+pub const MAX_LOCATION_LENGTH: usize = 100;
+pub const MAX_CHUNK_SIZE: usize = 9216;
+// finalize_image_post takes every chunk in one transaction, which can lock at most 64 accounts.
+// The rest go to the post, config, author, the program and a separate fee payer
+pub const MAX_IMAGE_CHUNKS: u8 = 58;
+pub const MAX_PINNED_POSTS: usize = 3;
+
+// Reaction kinds counted on each post, 0 = heart (like), 1 = laugh, 2 = wow, 3 = sad, 4 = angry, 5 = fire
+pub const REACTION_KINDS: usize = 6;
+pub const HEART_REACTION: u8 = 0;
+
+// Size of posts created before Post.version, see migrate_post
+pub const LEGACY_POST_SIZE: usize = 8 + 32 + 4 + MAX_CONTENT_LENGTH + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1;
+// Size of profiles created before the fields appended after UserProfile.bump, see migrate_user_profile
+pub const LEGACY_USER_PROFILE_SIZE: usize = 8 + 32 + 4 + MAX_USERNAME_LENGTH + 4 + MAX_DISPLAY_NAME_LENGTH + 4 + MAX_BIO_LENGTH + 4 + MAX_URL_LENGTH + 4 + MAX_URL_LENGTH + 4 + MAX_URL_LENGTH + 4 + MAX_LOCATION_LENGTH + 8 + 8 + 8 + 8 + 1 + 1;
+
 #[program]
 pub mod solcials {
     use super::*;
 
-    pub fn initialize(_ctx: Context<Initialize>) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        config.admin = ctx.accounts.admin.key();
+        config.reserved_usernames = ["admin", "administrator", "moderator", "official", "solcials", "support"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        config.verifiers = Vec::new();
+        config.paused = false;
+        config.max_content_length = MAX_CONTENT_LENGTH as u16;
+        config.max_username_length = MAX_USERNAME_LENGTH as u16;
+        config.max_display_name_length = MAX_DISPLAY_NAME_LENGTH as u16;
+        config.max_bio_length = MAX_BIO_LENGTH as u16;
+        config.max_url_length = MAX_URL_LENGTH as u16;
+        config.max_location_length = MAX_LOCATION_LENGTH as u16;
+        config.max_chunk_size = MAX_CHUNK_SIZE as u16;
+        config.text_post_fee_bps = 0; // Posting is free until fees are enabled
+        config.image_post_fee_bps = 0;
+        config.treasury_authority = ctx.accounts.admin.key();
+        config.verified_fee_discount_bps = 0;
+        config.token_holder_discount_bps = 0;
+        config.discount_mint = None;
+        config.discount_min_balance = 0;
+        config.fee_exempt_wallets = Vec::new();
+        config.bump = ctx.bumps.config;
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected = 0;
+        treasury.total_withdrawn = 0;
+        treasury.bump = ctx.bumps.treasury;
+
         msg!("solcials - decentralized social media on solana");
         Ok(())
     }
 
-    // Create a text post (free)
+    // Pause the program or tune its limits (admin only)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        paused: Option<bool>,
+        max_content_length: Option<u16>,
+        max_username_length: Option<u16>,
+        max_display_name_length: Option<u16>,
+        max_bio_length: Option<u16>,
+        max_url_length: Option<u16>,
+        max_location_length: Option<u16>,
+        max_chunk_size: Option<u16>,
+        text_post_fee_bps: Option<u16>,
+        image_post_fee_bps: Option<u16>,
+        treasury_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        if let Some(paused) = paused {
+            config.paused = paused;
+        }
+
+        if let Some(max_content_length) = max_content_length {
+            require!(max_content_length > 0 && max_content_length as usize <= MAX_CONTENT_LENGTH, SocialError::InvalidLimit);
+            config.max_content_length = max_content_length;
+        }
+
+        if let Some(max_username_length) = max_username_length {
+            require!(max_username_length >= 3 && max_username_length as usize <= MAX_USERNAME_LENGTH, SocialError::InvalidLimit);
+            config.max_username_length = max_username_length;
+        }
+
+        if let Some(max_display_name_length) = max_display_name_length {
+            require!(max_display_name_length > 0 && max_display_name_length as usize <= MAX_DISPLAY_NAME_LENGTH, SocialError::InvalidLimit);
+            config.max_display_name_length = max_display_name_length;
+        }
+
+        if let Some(max_bio_length) = max_bio_length {
+            require!(max_bio_length > 0 && max_bio_length as usize <= MAX_BIO_LENGTH, SocialError::InvalidLimit);
+            config.max_bio_length = max_bio_length;
+        }
+
+        if let Some(max_url_length) = max_url_length {
+            require!(max_url_length > 0 && max_url_length as usize <= MAX_URL_LENGTH, SocialError::InvalidLimit);
+            config.max_url_length = max_url_length;
+        }
+
+        if let Some(max_location_length) = max_location_length {
+            require!(max_location_length > 0 && max_location_length as usize <= MAX_LOCATION_LENGTH, SocialError::InvalidLimit);
+            config.max_location_length = max_location_length;
+        }
+
+        if let Some(max_chunk_size) = max_chunk_size {
+            require!(max_chunk_size > 0 && max_chunk_size as usize <= MAX_CHUNK_SIZE, SocialError::InvalidLimit);
+            config.max_chunk_size = max_chunk_size;
+        }
+
+        if let Some(text_post_fee_bps) = text_post_fee_bps {
+            require!(text_post_fee_bps <= 10_000, SocialError::InvalidFeeRate);
+            config.text_post_fee_bps = text_post_fee_bps;
+        }
+
+        if let Some(image_post_fee_bps) = image_post_fee_bps {
+            require!(image_post_fee_bps <= 10_000, SocialError::InvalidFeeRate);
+            config.image_post_fee_bps = image_post_fee_bps;
+        }
+
+        if let Some(treasury_authority) = treasury_authority {
+            config.treasury_authority = treasury_authority;
+        }
+
+        msg!("Config updated, paused: {}", config.paused);
+        Ok(())
+    }
+
+    // Withdraw collected fees (treasury authority only)
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let destination_info = ctx.accounts.destination.to_account_info();
+
+        // Keep the treasury rent exempt
+        let rent_floor = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info.lamports().saturating_sub(rent_floor);
+        require!(amount <= available, SocialError::InsufficientTreasuryBalance);
+
+        **treasury_info.try_borrow_mut_lamports()? -= amount;
+        **destination_info.try_borrow_mut_lamports()? += amount;
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_withdrawn += amount;
+
+        emit_cpi!(TreasuryWithdrawn {
+            authority: ctx.accounts.treasury_authority.key(),
+            destination: destination_info.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Withdrew {} lamports from treasury", amount);
+        Ok(())
+    }
+
+    // Hand the admin role to another wallet (admin only)
+    pub fn transfer_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.admin = new_admin;
+
+        msg!("Admin transferred to: {}", new_admin);
+        Ok(())
+    }
+
+    // Reserve a username so it cannot be claimed (admin only)
+    pub fn add_reserved_username(ctx: Context<UpdateConfig>, username: String) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let username = username.to_ascii_lowercase();
+
+        require!(!username.is_empty(), SocialError::UsernameTooShort);
+        require!(username.len() <= MAX_USERNAME_LENGTH, SocialError::UsernameTooLong);
+        require!(config.reserved_usernames.len() < 32, SocialError::TooManyReservedUsernames);
+
+        if !config.reserved_usernames.contains(&username) {
+            config.reserved_usernames.push(username);
+        }
+
+        msg!("Reserved usernames updated");
+        Ok(())
+    }
+
+    // Make a reserved username claimable again (admin only)
+    pub fn remove_reserved_username(ctx: Context<UpdateConfig>, username: String) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let username = username.to_ascii_lowercase();
+
+        let index = config
+            .reserved_usernames
+            .iter()
+            .position(|reserved| *reserved == username)
+            .ok_or(SocialError::ReservedUsernameNotFound)?;
+        config.reserved_usernames.remove(index);
+
+        msg!("Reserved usernames updated");
+        Ok(())
+    }
+
+    // Allow a wallet to verify profiles (admin only)
+    pub fn add_verifier(ctx: Context<UpdateConfig>, verifier: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(!config.verifiers.contains(&verifier), SocialError::VerifierAlreadyExists);
+        require!(config.verifiers.len() < 10, SocialError::TooManyVerifiers);
+        config.verifiers.push(verifier);
+
+        msg!("Verifier added: {}", verifier);
+        Ok(())
+    }
+
+    // Stop a wallet from verifying profiles (admin only)
+    pub fn remove_verifier(ctx: Context<UpdateConfig>, verifier: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        let index = config
+            .verifiers
+            .iter()
+            .position(|existing| *existing == verifier)
+            .ok_or(SocialError::VerifierNotFound)?;
+        config.verifiers.remove(index);
+
+        msg!("Verifier removed: {}", verifier);
+        Ok(())
+    }
+
+    // Set fee discounts for verified profiles and discount token holders (admin only)
+    pub fn update_fee_schedule(
+        ctx: Context<UpdateConfig>,
+        verified_fee_discount_bps: u16,
+        token_holder_discount_bps: u16,
+        discount_mint: Option<Pubkey>,
+        discount_min_balance: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(verified_fee_discount_bps <= 10_000, SocialError::InvalidFeeRate);
+        require!(token_holder_discount_bps <= 10_000, SocialError::InvalidFeeRate);
+
+        config.verified_fee_discount_bps = verified_fee_discount_bps;
+        config.token_holder_discount_bps = token_holder_discount_bps;
+        config.discount_mint = discount_mint;
+        config.discount_min_balance = discount_min_balance;
+
+        msg!("Fee schedule updated");
+        Ok(())
+    }
+
+    // Waive platform fees for a wallet (admin only)
+    pub fn add_fee_exempt_wallet(ctx: Context<UpdateConfig>, wallet: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(!config.fee_exempt_wallets.contains(&wallet), SocialError::WalletAlreadyFeeExempt);
+        require!(config.fee_exempt_wallets.len() < 10, SocialError::TooManyFeeExemptWallets);
+        config.fee_exempt_wallets.push(wallet);
+
+        msg!("Fee exempt wallet added: {}", wallet);
+        Ok(())
+    }
+
+    // Charge platform fees to a wallet again (admin only)
+    pub fn remove_fee_exempt_wallet(ctx: Context<UpdateConfig>, wallet: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        let index = config
+            .fee_exempt_wallets
+            .iter()
+            .position(|existing| *existing == wallet)
+            .ok_or(SocialError::WalletNotFeeExempt)?;
+        config.fee_exempt_wallets.remove(index);
+
+        msg!("Fee exempt wallet removed: {}", wallet);
+        Ok(())
+    }
+
+    // Create a text post (with the text post fee from Config)
     pub fn create_text_post(
         ctx: Context<CreateTextPost>,
         content: String,
-        timestamp: i64,
         reply_to: Option<Pubkey>,
     ) -> Result<()> {
         let post = &mut ctx.accounts.post;
+        let clock = Clock::get()?;
 
         // Validate content length
-        require!(content.len() <= 280, SocialError::ContentTooLong);
-        require!(content.len() > 0, SocialError::ContentEmpty);
-
+        require!(content.len() <= ctx.accounts.config.max_content_length as usize, SocialError::ContentTooLong);
+        require!(!content.is_empty(), SocialError::ContentEmpty);
+
+        // Collect the platform fee, a share of the post's rent after any discount
+        let fee_bps = effective_fee_bps(
+            &ctx.accounts.config,
+            ctx.accounts.config.text_post_fee_bps,
+            &ctx.accounts.user_profile,
+            ctx.accounts.discount_token_account.as_deref(),
+        );
+        let platform_fee = collect_post_fee(
+            &post.to_account_info(),
+            &ctx.accounts.author.to_account_info(),
+            &mut ctx.accounts.treasury,
+            fee_bps,
+        )?;
+
+        // Replies must point at an existing post, whose reply count is bumped
+        post.root = match (reply_to, ctx.accounts.parent_post.as_mut()) {
+            (Some(reply_to), Some(parent_post)) => {
+                require_keys_eq!(parent_post.key(), reply_to, SocialError::InvalidReplyParent);
+                require_not_blocked(
+                    ctx.accounts.parent_block_account.as_deref(),
+                    &parent_post.author,
+                    &ctx.accounts.author.key(),
+                    ctx.program_id,
+                )?;
+                parent_post.replies += 1;
+                parent_post.root
+            }
+            (None, None) => post.key(),
+            _ => return err!(SocialError::InvalidReplyParent),
+        };
+
+        post.version = 1;
         post.author = ctx.accounts.author.key();
         post.content = content;
         post.post_type = 0; // 0 = text post
         post.image_chunks = Vec::new();
         post.total_image_chunks = 0;
         post.reply_to = reply_to;
-        post.timestamp = timestamp; // Use provided timestamp
+        post.quote_of = None;
+        post.timestamp = clock.unix_timestamp;
         post.likes = 0;
         post.reposts = 0;
         post.replies = 0;
+        post.edited_at = None;
+        post.edit_count = 0;
+        post.image_finalized = false;
+        post.image_hash = [0; 32];
+        post.image_mime_type = String::new();
+        post.image_width = 0;
+        post.image_height = 0;
+        post.attachment_count = 0;
+        post.fee_paid = platform_fee;
+        post.reactions = [0; REACTION_KINDS];
+        post.open_revisions = 0;
+        post.open_attachments = 0;
         post.bump = ctx.bumps.post;
 
-        // Update user's post count
-        ctx.accounts.user_profile.post_count += 1;
-
-        msg!("Text post created by: {}", ctx.accounts.author.key());
+        // Update user's post count and advance the nonce seeding their next post
+        let profile = &mut ctx.accounts.user_profile;
+        profile.post_count += 1;
+        profile.post_nonce += 1;
+
+        if platform_fee > 0 {
+            emit_cpi!(FeeCollected {
+                payer: ctx.accounts.author.key(),
+                post: ctx.accounts.post.key(),
+                amount: platform_fee,
+                fee_bps,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        emit_cpi!(PostCreated {
+            post: ctx.accounts.post.key(),
+            author: ctx.accounts.author.key(),
+            root: ctx.accounts.post.root,
+            post_type: 0,
+            reply_to,
+            quote_of: None,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Text post created by: {} with platform fee: {}", ctx.accounts.author.key(), platform_fee);
         Ok(())
     }
 
-    // Create an image post (free)
+    // Create an image post (with the image post fee from Config)
     pub fn create_image_post(
         ctx: Context<CreateImagePost>,
         content: String,
-        timestamp: i64,
         reply_to: Option<Pubkey>,
     ) -> Result<()> {
         let post = &mut ctx.accounts.post;
+        let clock = Clock::get()?;
 
         // Validate content length
-        require!(content.len() <= 280, SocialError::ContentTooLong);
-        require!(content.len() > 0, SocialError::ContentEmpty);
-
+        require!(content.len() <= ctx.accounts.config.max_content_length as usize, SocialError::ContentTooLong);
+        require!(!content.is_empty(), SocialError::ContentEmpty);
+
+        // Collect the platform fee, a share of the post's rent after any discount
+        let fee_bps = effective_fee_bps(
+            &ctx.accounts.config,
+            ctx.accounts.config.image_post_fee_bps,
+            &ctx.accounts.user_profile,
+            ctx.accounts.discount_token_account.as_deref(),
+        );
+        let platform_fee = collect_post_fee(
+            &post.to_account_info(),
+            &ctx.accounts.author.to_account_info(),
+            &mut ctx.accounts.treasury,
+            fee_bps,
+        )?;
+
+        // Replies must point at an existing post, whose reply count is bumped
+        post.root = match (reply_to, ctx.accounts.parent_post.as_mut()) {
+            (Some(reply_to), Some(parent_post)) => {
+                require_keys_eq!(parent_post.key(), reply_to, SocialError::InvalidReplyParent);
+                require_not_blocked(
+                    ctx.accounts.parent_block_account.as_deref(),
+                    &parent_post.author,
+                    &ctx.accounts.author.key(),
+                    ctx.program_id,
+                )?;
+                parent_post.replies += 1;
+                parent_post.root
+            }
+            (None, None) => post.key(),
+            _ => return err!(SocialError::InvalidReplyParent),
+        };
+
+        post.version = 1;
         post.author = ctx.accounts.author.key();
         post.content = content;
         post.post_type = 1; // 1 = image post
         post.image_chunks = Vec::new(); // Will be populated when chunks are added
         post.total_image_chunks = 0;
         post.reply_to = reply_to;
-        post.timestamp = timestamp; // Use provided timestamp
+        post.quote_of = None;
+        post.timestamp = clock.unix_timestamp;
         post.likes = 0;
         post.reposts = 0;
         post.replies = 0;
+        post.edited_at = None;
+        post.edit_count = 0;
+        post.image_finalized = false;
+        post.image_hash = [0; 32];
+        post.image_mime_type = String::new();
+        post.image_width = 0;
+        post.image_height = 0;
+        post.attachment_count = 0;
+        post.fee_paid = platform_fee;
+        post.reactions = [0; REACTION_KINDS];
+        post.open_revisions = 0;
+        post.open_attachments = 0;
         post.bump = ctx.bumps.post;
 
-        // Update user's post count
-        ctx.accounts.user_profile.post_count += 1;
+        // Update user's post count and advance the nonce seeding their next post
+        let profile = &mut ctx.accounts.user_profile;
+        profile.post_count += 1;
+        profile.post_nonce += 1;
+
+        if platform_fee > 0 {
+            emit_cpi!(FeeCollected {
+                payer: ctx.accounts.author.key(),
+                post: ctx.accounts.post.key(),
+                amount: platform_fee,
+                fee_bps,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        emit_cpi!(PostCreated {
+            post: ctx.accounts.post.key(),
+            author: ctx.accounts.author.key(),
+            root: ctx.accounts.post.root,
+            post_type: 1,
+            reply_to,
+            quote_of: None,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Image post created by: {} with platform fee: {}", ctx.accounts.author.key(), platform_fee);
+        Ok(())
+    }
+
+    // Create a text post quoting another post (with the text post fee from Config)
+    pub fn quote_post(
+        ctx: Context<QuotePost>,
+        content: String,
+    ) -> Result<()> {
+        let post = &mut ctx.accounts.post;
+        let clock = Clock::get()?;
+
+        // Validate content length
+        require!(content.len() <= ctx.accounts.config.max_content_length as usize, SocialError::ContentTooLong);
+        require!(!content.is_empty(), SocialError::ContentEmpty);
+
+        // Collect the platform fee, a share of the post's rent after any discount
+        let fee_bps = effective_fee_bps(
+            &ctx.accounts.config,
+            ctx.accounts.config.text_post_fee_bps,
+            &ctx.accounts.user_profile,
+            ctx.accounts.discount_token_account.as_deref(),
+        );
+        let platform_fee = collect_post_fee(
+            &post.to_account_info(),
+            &ctx.accounts.author.to_account_info(),
+            &mut ctx.accounts.treasury,
+            fee_bps,
+        )?;
+
+        post.version = 1;
+        post.author = ctx.accounts.author.key();
+        post.root = post.key();
+        post.content = content;
+        post.post_type = 0; // 0 = text post
+        post.image_chunks = Vec::new();
+        post.total_image_chunks = 0;
+        post.reply_to = None;
+        post.quote_of = Some(ctx.accounts.quoted_post.key());
+        post.timestamp = clock.unix_timestamp;
+        post.likes = 0;
+        post.reposts = 0;
+        post.replies = 0;
+        post.edited_at = None;
+        post.edit_count = 0;
+        post.image_finalized = false;
+        post.image_hash = [0; 32];
+        post.image_mime_type = String::new();
+        post.image_width = 0;
+        post.image_height = 0;
+        post.attachment_count = 0;
+        post.fee_paid = platform_fee;
+        post.reactions = [0; REACTION_KINDS];
+        post.open_revisions = 0;
+        post.open_attachments = 0;
+        post.bump = ctx.bumps.post;
 
-        msg!("Image post created by: {}", ctx.accounts.author.key());
+        // Update user's post count and advance the nonce seeding their next post
+        let profile = &mut ctx.accounts.user_profile;
+        profile.post_count += 1;
+        profile.post_nonce += 1;
+
+        if platform_fee > 0 {
+            emit_cpi!(FeeCollected {
+                payer: ctx.accounts.author.key(),
+                post: ctx.accounts.post.key(),
+                amount: platform_fee,
+                fee_bps,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        emit_cpi!(PostCreated {
+            post: ctx.accounts.post.key(),
+            author: ctx.accounts.author.key(),
+            root: ctx.accounts.post.root,
+            post_type: 0,
+            reply_to: None,
+            quote_of: ctx.accounts.post.quote_of,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Quote post created by: {} with platform fee: {}", ctx.accounts.author.key(), platform_fee);
         Ok(())
     }
 
@@ -85,8 +573,16 @@ pub mod solcials {
         let chunk = &mut ctx.accounts.image_chunk;
         let post = &mut ctx.accounts.post;
 
-        // Validate chunk size (max 9KB to stay under 10KB account limit)
-        require!(chunk_data.len() <= 9216, SocialError::ChunkTooLarge);
+        // Validate chunk size (at most 9KB to stay under 10KB account limit)
+        require!(chunk_data.len() <= ctx.accounts.config.max_chunk_size as usize, SocialError::ChunkTooLarge);
+
+        // Chunk must fall inside the declared total, which cannot change once set
+        require!(total_chunks <= MAX_IMAGE_CHUNKS, SocialError::TooManyChunks);
+        require!(chunk_index < total_chunks, SocialError::ChunkIndexOutOfRange);
+        require!(
+            post.total_image_chunks == 0 || post.total_image_chunks == total_chunks,
+            SocialError::TotalChunksMismatch
+        );
 
         let post_key = post.key();
         chunk.post = post_key;
@@ -99,10 +595,333 @@ pub mod solcials {
         post.image_chunks.push(ctx.accounts.image_chunk.key());
         post.total_image_chunks = total_chunks;
 
+        emit_cpi!(ImageChunkAdded {
+            post: post_key,
+            chunk: ctx.accounts.image_chunk.key(),
+            chunk_index,
+            total_chunks,
+        });
+
         msg!("Image chunk {} of {} added to post", chunk_index + 1, total_chunks);
         Ok(())
     }
 
+    // Finalize an image post once every chunk has been uploaded
+    pub fn finalize_image_post(
+        ctx: Context<FinalizeImagePost>,
+        mime_type: String,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let post = &mut ctx.accounts.post;
+
+        // Validate image metadata
+        require!(mime_type.len() <= 32, SocialError::MimeTypeTooLong);
+        require!(mime_type.starts_with("image/"), SocialError::InvalidMimeType);
+        require!(width > 0 && height > 0, SocialError::InvalidImageDimensions);
+
+        // Remaining accounts: every chunk of the post, ordered by chunk_index
+        let total_chunks = post.total_image_chunks as usize;
+        require!(
+            total_chunks > 0
+                && post.image_chunks.len() == total_chunks
+                && ctx.remaining_accounts.len() == total_chunks,
+            SocialError::IncompleteImage
+        );
+
+        let mut hasher = hash::Hasher::default();
+        for (index, chunk_info) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                post.image_chunks.contains(chunk_info.key) && chunk_info.owner == &crate::ID,
+                SocialError::InvalidImageChunk
+            );
+
+            // Read the chunk in place, deserializing every 9KB chunk would exhaust the heap.
+            // Layout: discriminator (8) + post (32) + chunk_index (1) + total_chunks (1) + data_len (4) + data
+            let data = chunk_info.try_borrow_data()?;
+            require!(data[40] as usize == index, SocialError::IncompleteImage);
+            let data_len = u32::from_le_bytes(data[42..46].try_into().unwrap()) as usize;
+            hasher.hash(&data[46..46 + data_len]);
+        }
+
+        post.image_finalized = true;
+        post.image_hash = hasher.result().to_bytes();
+        post.image_mime_type = mime_type;
+        post.image_width = width;
+        post.image_height = height;
+
+        msg!("Image post finalized with {} chunks", total_chunks);
+        Ok(())
+    }
+
+    // Attach off-chain or on-chain media to an existing post
+    pub fn add_attachment(
+        ctx: Context<AddAttachment>,
+        kind: u8,
+        uri: String,
+        content_hash: [u8; 32],
+        mime_type: String,
+    ) -> Result<()> {
+        let attachment = &mut ctx.accounts.attachment;
+        let post = &mut ctx.accounts.post;
+
+        require!((post.attachment_count as usize) < 4, SocialError::TooManyAttachments);
+        require!(uri.len() <= MAX_URL_LENGTH, SocialError::AttachmentUriTooLong);
+        require!(mime_type.len() <= 32, SocialError::MimeTypeTooLong);
+        require!(mime_type.contains('/'), SocialError::InvalidAttachmentMimeType);
+
+        // Validate the reference for its storage kind
+        let uri_valid = match kind {
+            // Arweave transaction id (43 base64url characters)
+            0 => {
+                uri.len() == 43
+                    && uri.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            }
+            // IPFS CID (v0 or v1)
+            1 => uri.len() >= 46 && uri.bytes().all(|b| b.is_ascii_alphanumeric()),
+            // HTTPS URL
+            2 => uri.starts_with("https://") && uri.len() > 8,
+            // On-chain image chunks of this post, no URI
+            3 => uri.is_empty() && post.post_type == 1,
+            _ => return err!(SocialError::InvalidAttachmentKind),
+        };
+        require!(uri_valid, SocialError::InvalidAttachmentUri);
+
+        attachment.post = post.key();
+        attachment.index = post.attachment_count;
+        attachment.kind = kind;
+        attachment.uri = uri;
+        attachment.content_hash = content_hash;
+        attachment.mime_type = mime_type;
+        attachment.bump = ctx.bumps.attachment;
+
+        post.attachment_count += 1;
+        post.open_attachments += 1;
+
+        msg!("Attachment {} added to post", attachment.index);
+        Ok(())
+    }
+
+    // Edit an existing post, archiving the previous content in a revision account
+    pub fn edit_post(ctx: Context<EditPost>, content: String) -> Result<()> {
+        let post = &mut ctx.accounts.post;
+        let revision = &mut ctx.accounts.revision;
+        let clock = Clock::get()?;
+
+        // Validate content length
+        require!(content.len() <= ctx.accounts.config.max_content_length as usize, SocialError::ContentTooLong);
+        require!(!content.is_empty(), SocialError::ContentEmpty);
+
+        // Archive the content being replaced
+        revision.post = post.key();
+        revision.revision = post.edit_count;
+        revision.content = std::mem::replace(&mut post.content, content);
+        revision.timestamp = clock.unix_timestamp;
+        revision.bump = ctx.bumps.revision;
+
+        post.edited_at = Some(clock.unix_timestamp);
+        post.edit_count = post
+            .edit_count
+            .checked_add(1)
+            .ok_or(SocialError::TooManyEdits)?;
+        post.open_revisions += 1;
+
+        emit_cpi!(PostEdited {
+            post: post.key(),
+            author: ctx.accounts.author.key(),
+            revision: revision.key(),
+            edit_count: post.edit_count,
+            edited_at: clock.unix_timestamp,
+        });
+
+        msg!("Post edited by: {} (revision {})", ctx.accounts.author.key(), revision.revision);
+        Ok(())
+    }
+
+    // Delete a post along with its image chunks and revisions, refunding rent to the author
+    pub fn delete_post<'info>(ctx: Context<'_, '_, 'info, 'info, DeletePost<'info>>) -> Result<()> {
+        let post = &ctx.accounts.post;
+        let post_key = post.key();
+        let author = ctx.accounts.author.to_account_info();
+
+        // Remaining accounts: every image chunk still open (in post.image_chunks order), then
+        // every open revision, then every open attachment, so nothing is left behind holding
+        // rent. Posts with too many for one transaction close some with close_post_accounts first
+        let chunk_count = post.image_chunks.len();
+        let revision_count = post.open_revisions as usize;
+        require!(
+            ctx.remaining_accounts.len()
+                == chunk_count + revision_count + post.open_attachments as usize,
+            SocialError::IncompletePostAccounts
+        );
+        let (chunk_infos, other_infos) = ctx.remaining_accounts.split_at(chunk_count);
+        let (revision_infos, attachment_infos) = other_infos.split_at(revision_count);
+
+        // Chunks are closed without deserializing them, 9KB each would exhaust the heap
+        for (chunk_info, expected) in chunk_infos.iter().zip(post.image_chunks.iter()) {
+            require_keys_eq!(chunk_info.key(), *expected, SocialError::InvalidImageChunk);
+            close_account(chunk_info, &author)?;
+        }
+
+        // Closing each one as it is checked rejects the same account passed twice
+        for revision_info in revision_infos {
+            let revision = Account::<PostRevision>::try_from(revision_info)?;
+            require_keys_eq!(revision.post, post_key, SocialError::InvalidRevision);
+            revision.close(author.clone())?;
+        }
+
+        for attachment_info in attachment_infos {
+            let attachment = Account::<Attachment>::try_from(attachment_info)?;
+            require_keys_eq!(attachment.post, post_key, SocialError::InvalidAttachment);
+            attachment.close(author.clone())?;
+        }
+
+        // Leave a tombstone behind if requested so replies can still render "deleted"
+        if let Some(tombstone) = ctx.accounts.tombstone.as_mut() {
+            let clock = Clock::get()?;
+            tombstone.post = post_key;
+            tombstone.author = ctx.accounts.author.key();
+            tombstone.deleted_at = clock.unix_timestamp;
+            tombstone.bump = ctx.bumps.tombstone;
+        }
+
+        // Replies give back the count they added to their parent, if it still exists
+        if let Some(parent_post) = ctx.accounts.parent_post.as_mut() {
+            require!(post.reply_to == Some(parent_post.key()), SocialError::InvalidReplyParent);
+            parent_post.replies = parent_post
+                .replies
+                .checked_sub(1)
+                .ok_or(SocialError::CounterUnderflow)?;
+        }
+
+        // Update user's post count and drop the post from their pins
+        let profile = &mut ctx.accounts.user_profile;
+        profile.post_count = profile
+            .post_count
+            .checked_sub(1)
+            .ok_or(SocialError::CounterUnderflow)?;
+        profile.pinned_posts.retain(|pinned| *pinned != post_key);
+
+        emit_cpi!(PostDeleted {
+            post: post_key,
+            author: ctx.accounts.author.key(),
+            tombstone: ctx.accounts.tombstone.is_some(),
+        });
+
+        msg!("Post deleted by: {}", ctx.accounts.author.key());
+        Ok(())
+    }
+
+    // Close some of a post's image chunks, revisions and attachments ahead of delete_post,
+    // for posts with more than fit in one transaction. Remaining accounts can be any mix
+    pub fn close_post_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClosePostAccounts<'info>>,
+    ) -> Result<()> {
+        let post = &mut ctx.accounts.post;
+        let post_key = post.key();
+        let author = ctx.accounts.author.to_account_info();
+
+        for account_info in ctx.remaining_accounts {
+            require_keys_eq!(*account_info.owner, crate::ID, SocialError::InvalidPostAccount);
+            let discriminator: [u8; 8] = {
+                let data = account_info.try_borrow_data()?;
+                require!(data.len() >= 8, SocialError::InvalidPostAccount);
+                data[..8].try_into().unwrap()
+            };
+
+            if discriminator == ImageChunk::DISCRIMINATOR {
+                // Chunks are closed without deserializing them, 9KB each would exhaust the heap
+                require!(post.image_chunks.contains(account_info.key), SocialError::InvalidImageChunk);
+                post.image_chunks.retain(|chunk| chunk != account_info.key);
+                post.image_finalized = false;
+                close_account(account_info, &author)?;
+            } else if discriminator == PostRevision::DISCRIMINATOR {
+                let revision = Account::<PostRevision>::try_from(account_info)?;
+                require_keys_eq!(revision.post, post_key, SocialError::InvalidRevision);
+                post.open_revisions = post
+                    .open_revisions
+                    .checked_sub(1)
+                    .ok_or(SocialError::CounterUnderflow)?;
+                revision.close(author.clone())?;
+            } else if discriminator == Attachment::DISCRIMINATOR {
+                let attachment = Account::<Attachment>::try_from(account_info)?;
+                require_keys_eq!(attachment.post, post_key, SocialError::InvalidAttachment);
+                post.open_attachments = post
+                    .open_attachments
+                    .checked_sub(1)
+                    .ok_or(SocialError::CounterUnderflow)?;
+                attachment.close(author.clone())?;
+            } else {
+                return err!(SocialError::InvalidPostAccount);
+            }
+        }
+
+        msg!("Closed {} accounts of post {}", ctx.remaining_accounts.len(), post_key);
+        Ok(())
+    }
+
+    // Rewrite a post created before the version byte into the current layout, anyone may pay
+    pub fn migrate_post(ctx: Context<MigratePost>) -> Result<()> {
+        let post_info = ctx.accounts.post.to_account_info();
+        let post_key = post_info.key();
+
+        let legacy = {
+            let data = post_info.try_borrow_data()?;
+            require!(
+                data.len() == LEGACY_POST_SIZE && data[..8] == Post::DISCRIMINATOR,
+                SocialError::PostNotLegacy
+            );
+            LegacyPost::deserialize(&mut &data[8..])
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+        };
+
+        // Grow to the size create_text_post allocates plus any chunks already referenced
+        let space = 8 + 1 + 32 + 32 + 4 + MAX_CONTENT_LENGTH + 1 + 4 + 1 + 1 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 32 + 4 + 32 + 4 + 4 + 1 + 8 + 8 * REACTION_KINDS + 2 + 1 + 1
+            + 32 * legacy.image_chunks.len();
+        let rent = Rent::get()?.minimum_balance(space).saturating_sub(post_info.lamports());
+        if rent > 0 {
+            transfer_lamports(&ctx.accounts.payer.to_account_info(), &post_info, rent)?;
+        }
+        post_info.realloc(space, true)?;
+
+        let mut reactions = [0; REACTION_KINDS];
+        reactions[HEART_REACTION as usize] = legacy.likes;
+
+        // Legacy replies never recorded their thread, so the parent stands in as root
+        let post = Post {
+            version: 1,
+            author: legacy.author,
+            root: legacy.reply_to.unwrap_or(post_key),
+            content: legacy.content,
+            post_type: legacy.post_type,
+            image_chunks: legacy.image_chunks,
+            total_image_chunks: legacy.total_image_chunks,
+            reply_to: legacy.reply_to,
+            quote_of: None,
+            timestamp: legacy.timestamp,
+            likes: legacy.likes,
+            reposts: legacy.reposts,
+            replies: legacy.replies,
+            edited_at: None,
+            edit_count: 0,
+            image_finalized: false,
+            image_hash: [0; 32],
+            image_mime_type: String::new(),
+            image_width: 0,
+            image_height: 0,
+            attachment_count: 0,
+            fee_paid: 0,
+            reactions,
+            open_revisions: 0,
+            open_attachments: 0,
+            bump: legacy.bump,
+        };
+        post.try_serialize(&mut &mut post_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Post {} migrated to version {}", post_key, post.version);
+        Ok(())
+    }
+
     // Follow a user
     pub fn follow_user(ctx: Context<FollowUser>) -> Result<()> {
         let follow_account = &mut ctx.accounts.follow_account;
@@ -117,17 +936,92 @@ pub mod solcials {
         ctx.accounts.follower_profile.following_count += 1;
         ctx.accounts.following_profile.followers_count += 1;
 
+        emit_cpi!(Followed {
+            follower: ctx.accounts.follower.key(),
+            following: ctx.accounts.following.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
         msg!("User {} followed {}", ctx.accounts.follower.key(), ctx.accounts.following.key());
         Ok(())
     }
 
     // Unfollow a user
-    pub fn unfollow_user(_ctx: Context<UnfollowUser>) -> Result<()> {
-        // The account will be closed and lamports returned
+    pub fn unfollow_user(ctx: Context<UnfollowUser>) -> Result<()> {
+        // Update follower/following counts, the account will be closed and lamports returned
+        let follower_profile = &mut ctx.accounts.follower_profile;
+        follower_profile.following_count = follower_profile
+            .following_count
+            .checked_sub(1)
+            .ok_or(SocialError::CounterUnderflow)?;
+
+        let following_profile = &mut ctx.accounts.following_profile;
+        following_profile.followers_count = following_profile
+            .followers_count
+            .checked_sub(1)
+            .ok_or(SocialError::CounterUnderflow)?;
+
+        emit_cpi!(Unfollowed {
+            follower: ctx.accounts.follower.key(),
+            following: ctx.accounts.following.key(),
+        });
+
         msg!("User unfollowed successfully");
         Ok(())
     }
 
+    // Ask to follow a private profile
+    pub fn request_follow(ctx: Context<RequestFollow>) -> Result<()> {
+        let follow_request = &mut ctx.accounts.follow_request;
+        let clock = Clock::get()?;
+
+        follow_request.requester = ctx.accounts.requester.key();
+        follow_request.target = ctx.accounts.target.key();
+        follow_request.timestamp = clock.unix_timestamp;
+        follow_request.bump = ctx.bumps.follow_request;
+
+        msg!("User {} requested to follow {}", ctx.accounts.requester.key(), ctx.accounts.target.key());
+        Ok(())
+    }
+
+    // Approve a follow request, the target pays for the follow relation
+    pub fn approve_follow_request(ctx: Context<ApproveFollowRequest>) -> Result<()> {
+        let follow_account = &mut ctx.accounts.follow_account;
+        let clock = Clock::get()?;
+
+        follow_account.follower = ctx.accounts.requester.key();
+        follow_account.following = ctx.accounts.target.key();
+        follow_account.timestamp = clock.unix_timestamp;
+        follow_account.bump = ctx.bumps.follow_account;
+
+        // Update follower/following counts, the request will be closed and lamports returned
+        ctx.accounts.follower_profile.following_count += 1;
+        ctx.accounts.following_profile.followers_count += 1;
+
+        emit_cpi!(Followed {
+            follower: ctx.accounts.requester.key(),
+            following: ctx.accounts.target.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("User {} approved follow request from {}", ctx.accounts.target.key(), ctx.accounts.requester.key());
+        Ok(())
+    }
+
+    // Reject a follow request
+    pub fn reject_follow_request(_ctx: Context<RejectFollowRequest>) -> Result<()> {
+        // The account will be closed and lamports returned to the requester
+        msg!("Follow request rejected");
+        Ok(())
+    }
+
+    // Cancel a pending follow request
+    pub fn cancel_follow_request(_ctx: Context<CancelFollowRequest>) -> Result<()> {
+        // The account will be closed and lamports returned
+        msg!("Follow request cancelled");
+        Ok(())
+    }
+
     // Like a post
     pub fn like_post(ctx: Context<LikePost>) -> Result<()> {
         let like_account = &mut ctx.accounts.like_account;
@@ -138,8 +1032,16 @@ pub mod solcials {
         like_account.timestamp = clock.unix_timestamp;
         like_account.bump = ctx.bumps.like_account;
 
-        // Increment like count on post
+        // Increment like count on post, likes are the heart reaction
         ctx.accounts.post.likes += 1;
+        ctx.accounts.post.reactions[HEART_REACTION as usize] += 1;
+
+        emit_cpi!(Liked {
+            post: ctx.accounts.post.key(),
+            user: ctx.accounts.user.key(),
+            likes: ctx.accounts.post.likes,
+            timestamp: clock.unix_timestamp,
+        });
 
         msg!("Post liked by: {}", ctx.accounts.user.key());
         Ok(())
@@ -148,11 +1050,214 @@ pub mod solcials {
     // Unlike a post
     pub fn unlike_post(ctx: Context<UnlikePost>) -> Result<()> {
         // Decrement like count on post
-        ctx.accounts.post.likes -= 1;
+        let post = &mut ctx.accounts.post;
+        post.likes = post.likes.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+        let hearts = &mut post.reactions[HEART_REACTION as usize];
+        *hearts = hearts.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+
+        emit_cpi!(Unliked {
+            post: post.key(),
+            user: ctx.accounts.user.key(),
+            likes: post.likes,
+        });
+
         msg!("Post unliked");
         Ok(())
     }
 
+    // React to a post, the heart reaction goes through like_post
+    pub fn react_to_post(ctx: Context<ReactToPost>, reaction: u8) -> Result<()> {
+        require!((reaction as usize) < REACTION_KINDS, SocialError::InvalidReaction);
+        require!(reaction != HEART_REACTION, SocialError::UseLikeForHeart);
+
+        let reaction_account = &mut ctx.accounts.reaction_account;
+        let clock = Clock::get()?;
+
+        reaction_account.user = ctx.accounts.user.key();
+        reaction_account.post = ctx.accounts.post.key();
+        reaction_account.reaction = reaction;
+        reaction_account.timestamp = clock.unix_timestamp;
+        reaction_account.bump = ctx.bumps.reaction_account;
+
+        // Increment the reaction's count on post
+        let post = &mut ctx.accounts.post;
+        post.reactions[reaction as usize] += 1;
+
+        emit_cpi!(Reacted {
+            post: post.key(),
+            user: ctx.accounts.user.key(),
+            reaction,
+            count: post.reactions[reaction as usize],
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Post reacted to by: {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Remove a reaction from a post
+    pub fn remove_reaction(ctx: Context<RemoveReaction>, reaction: u8) -> Result<()> {
+        // Decrement the reaction's count on post, the account will be closed and lamports returned
+        let post = &mut ctx.accounts.post;
+        let count = &mut post.reactions[reaction as usize];
+        *count = count.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+
+        emit_cpi!(ReactionRemoved {
+            post: post.key(),
+            user: ctx.accounts.user.key(),
+            reaction,
+            count: post.reactions[reaction as usize],
+        });
+
+        msg!("Reaction removed");
+        Ok(())
+    }
+
+    // Block a user, removing any follow between the two wallets
+    pub fn block_user(ctx: Context<BlockUser>) -> Result<()> {
+        let block_account = &mut ctx.accounts.block_account;
+        let clock = Clock::get()?;
+
+        block_account.blocker = ctx.accounts.blocker.key();
+        block_account.blocked = ctx.accounts.blocked.key();
+        block_account.timestamp = clock.unix_timestamp;
+        block_account.bump = ctx.bumps.block_account;
+
+        let blocker_profile = &mut ctx.accounts.blocker_profile;
+        let blocked_profile_info = ctx.accounts.blocked_profile.to_account_info();
+        let mut blocked_profile = if blocked_profile_info.data_is_empty() {
+            None
+        } else {
+            Some(UserProfile::try_deserialize(&mut &blocked_profile_info.try_borrow_data()?[..])?)
+        };
+
+        // The follow accounts are pinned to FollowRelation PDAs by their seeds, so they are
+        // closed without deserializing. Remove the blocker's follow of the blocked user
+        let blocker_follow = ctx.accounts.blocker_follow.to_account_info();
+        if !blocker_follow.data_is_empty() {
+            close_account(&blocker_follow, &ctx.accounts.blocker.to_account_info())?;
+            blocker_profile.following_count = blocker_profile
+                .following_count
+                .checked_sub(1)
+                .ok_or(SocialError::CounterUnderflow)?;
+            if let Some(blocked_profile) = blocked_profile.as_mut() {
+                blocked_profile.followers_count = blocked_profile
+                    .followers_count
+                    .checked_sub(1)
+                    .ok_or(SocialError::CounterUnderflow)?;
+            }
+
+            emit_cpi!(Unfollowed {
+                follower: ctx.accounts.blocker.key(),
+                following: ctx.accounts.blocked.key(),
+            });
+        }
+
+        // Remove the blocked user's follow of the blocker, refunding them
+        let blocked_follow = ctx.accounts.blocked_follow.to_account_info();
+        if !blocked_follow.data_is_empty() {
+            close_account(&blocked_follow, &ctx.accounts.blocked.to_account_info())?;
+            blocker_profile.followers_count = blocker_profile
+                .followers_count
+                .checked_sub(1)
+                .ok_or(SocialError::CounterUnderflow)?;
+            if let Some(blocked_profile) = blocked_profile.as_mut() {
+                blocked_profile.following_count = blocked_profile
+                    .following_count
+                    .checked_sub(1)
+                    .ok_or(SocialError::CounterUnderflow)?;
+            }
+
+            emit_cpi!(Unfollowed {
+                follower: ctx.accounts.blocked.key(),
+                following: ctx.accounts.blocker.key(),
+            });
+        }
+
+        if let Some(blocked_profile) = blocked_profile {
+            blocked_profile.try_serialize(&mut &mut blocked_profile_info.try_borrow_mut_data()?[..])?;
+        }
+
+        msg!("User {} blocked {}", ctx.accounts.blocker.key(), ctx.accounts.blocked.key());
+        Ok(())
+    }
+
+    // Unblock a user
+    pub fn unblock_user(_ctx: Context<UnblockUser>) -> Result<()> {
+        // The account will be closed and lamports returned
+        msg!("User unblocked successfully");
+        Ok(())
+    }
+
+    // Mute a user, clients hide their posts but nothing is enforced
+    pub fn mute_user(ctx: Context<MuteUser>) -> Result<()> {
+        let mute_account = &mut ctx.accounts.mute_account;
+        let clock = Clock::get()?;
+
+        mute_account.muter = ctx.accounts.muter.key();
+        mute_account.muted = ctx.accounts.muted.key();
+        mute_account.timestamp = clock.unix_timestamp;
+        mute_account.bump = ctx.bumps.mute_account;
+
+        msg!("User muted successfully");
+        Ok(())
+    }
+
+    // Unmute a user
+    pub fn unmute_user(_ctx: Context<UnmuteUser>) -> Result<()> {
+        // The account will be closed and lamports returned
+        msg!("User unmuted successfully");
+        Ok(())
+    }
+
+    // Repost a post
+    pub fn repost(ctx: Context<Repost>) -> Result<()> {
+        let repost_account = &mut ctx.accounts.repost_account;
+        let clock = Clock::get()?;
+
+        repost_account.user = ctx.accounts.user.key();
+        repost_account.post = ctx.accounts.post.key();
+        repost_account.timestamp = clock.unix_timestamp;
+        repost_account.bump = ctx.bumps.repost_account;
+
+        // Increment repost count on post
+        ctx.accounts.post.reposts += 1;
+
+        msg!("Post reposted by: {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Undo a repost
+    pub fn undo_repost(ctx: Context<UndoRepost>) -> Result<()> {
+        // Decrement repost count on post
+        let post = &mut ctx.accounts.post;
+        post.reposts = post.reposts.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+        msg!("Repost undone");
+        Ok(())
+    }
+
+    // Bookmark a post, the post itself is left untouched so its author can't see bookmarks
+    pub fn bookmark_post(ctx: Context<BookmarkPost>, folder: Option<u8>) -> Result<()> {
+        let bookmark = &mut ctx.accounts.bookmark;
+        let clock = Clock::get()?;
+
+        bookmark.user = ctx.accounts.user.key();
+        bookmark.post = ctx.accounts.post.key();
+        bookmark.folder = folder;
+        bookmark.created_at = clock.unix_timestamp;
+        bookmark.bump = ctx.bumps.bookmark;
+
+        msg!("Post bookmarked");
+        Ok(())
+    }
+
+    // Remove a bookmark
+    pub fn remove_bookmark(_ctx: Context<RemoveBookmark>) -> Result<()> {
+        // The account will be closed and lamports returned
+        msg!("Bookmark removed");
+        Ok(())
+    }
+
     // Initialize user profile
     pub fn initialize_user_profile(ctx: Context<InitializeUserProfile>) -> Result<()> {
         let profile = &mut ctx.accounts.user_profile;
@@ -172,74 +1277,667 @@ pub mod solcials {
         profile.created_at = clock.unix_timestamp;
         profile.verified = false;
         profile.bump = ctx.bumps.user_profile;
+        profile.post_nonce = 0;
+        profile.verified_by = None;
+        profile.verified_at = None;
+        profile.revocation_reason = None;
+        profile.private = false;
+        profile.pinned_posts = Vec::new();
+
+        // A profile recreated after close_user_profile continues its post nonce, so new posts
+        // never land on a deleted post's address where old likes and reposts still point
+        let profile_tombstone = ctx.accounts.profile_tombstone.to_account_info();
+        if !profile_tombstone.data_is_empty() {
+            let tombstone =
+                ProfileTombstone::try_deserialize(&mut &profile_tombstone.try_borrow_data()?[..])?;
+            profile.post_nonce = tombstone.post_nonce;
+            close_account(&profile_tombstone, &ctx.accounts.user.to_account_info())?;
+        }
 
         msg!("User profile created for: {}", ctx.accounts.user.key());
         Ok(())
     }
 
+    // Close the caller's likes, reactions, reposts, follows and image chunks in batches, keeping the
+    // counters on the posts and profiles they point at consistent. Remaining accounts are
+    // pairs of a relation or chunk followed by the post or followed profile it references
+    pub fn reclaim_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReclaimAccounts<'info>>,
+    ) -> Result<()> {
+        let user = ctx.accounts.user.to_account_info();
+        let user_key = user.key();
+        let profile = &mut ctx.accounts.user_profile;
+
+        require!(ctx.remaining_accounts.chunks_exact(2).remainder().is_empty(), SocialError::InvalidReclaimAccount);
+
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let (account_info, counterpart_info) = (&pair[0], &pair[1]);
+            require_keys_eq!(*account_info.owner, crate::ID, SocialError::InvalidReclaimAccount);
+
+            // Counterparts that were already closed have no counter left to update
+            let counterpart_closed = counterpart_info.data_is_empty();
+            let (discriminator, chunk_post) = {
+                let data = account_info.try_borrow_data()?;
+                require!(data.len() >= 40, SocialError::InvalidReclaimAccount);
+                let discriminator: [u8; 8] = data[..8].try_into().unwrap();
+                let chunk_post = Pubkey::try_from(&data[8..40]).unwrap();
+                (discriminator, chunk_post)
+            };
+
+            if discriminator == LikeRelation::DISCRIMINATOR {
+                let like = Account::<LikeRelation>::try_from(account_info)?;
+                require_keys_eq!(like.user, user_key, SocialError::InvalidReclaimAccount);
+                require_keys_eq!(like.post, counterpart_info.key(), SocialError::InvalidReclaimAccount);
+
+                let mut likes = 0;
+                if !counterpart_closed {
+                    let mut post = Account::<Post>::try_from(counterpart_info)?;
+                    post.likes = post.likes.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+                    let hearts = &mut post.reactions[HEART_REACTION as usize];
+                    *hearts = hearts.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+                    post.exit(ctx.program_id)?;
+                    likes = post.likes;
+                }
+                like.close(user.clone())?;
+
+                emit_cpi!(Unliked {
+                    post: counterpart_info.key(),
+                    user: user_key,
+                    likes,
+                });
+            } else if discriminator == ReactionRelation::DISCRIMINATOR {
+                let reaction = Account::<ReactionRelation>::try_from(account_info)?;
+                require_keys_eq!(reaction.user, user_key, SocialError::InvalidReclaimAccount);
+                require_keys_eq!(reaction.post, counterpart_info.key(), SocialError::InvalidReclaimAccount);
+
+                let mut count = 0;
+                if !counterpart_closed {
+                    let mut post = Account::<Post>::try_from(counterpart_info)?;
+                    let reactions = &mut post.reactions[reaction.reaction as usize];
+                    *reactions = reactions.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+                    count = *reactions;
+                    post.exit(ctx.program_id)?;
+                }
+                let reaction_kind = reaction.reaction;
+                reaction.close(user.clone())?;
+
+                emit_cpi!(ReactionRemoved {
+                    post: counterpart_info.key(),
+                    user: user_key,
+                    reaction: reaction_kind,
+                    count,
+                });
+            } else if discriminator == RepostRelation::DISCRIMINATOR {
+                let repost = Account::<RepostRelation>::try_from(account_info)?;
+                require_keys_eq!(repost.user, user_key, SocialError::InvalidReclaimAccount);
+                require_keys_eq!(repost.post, counterpart_info.key(), SocialError::InvalidReclaimAccount);
+
+                if !counterpart_closed {
+                    let mut post = Account::<Post>::try_from(counterpart_info)?;
+                    post.reposts = post.reposts.checked_sub(1).ok_or(SocialError::CounterUnderflow)?;
+                    post.exit(ctx.program_id)?;
+                }
+                repost.close(user.clone())?;
+            } else if discriminator == FollowRelation::DISCRIMINATOR {
+                let follow = Account::<FollowRelation>::try_from(account_info)?;
+                require_keys_eq!(follow.follower, user_key, SocialError::InvalidReclaimAccount);
+                let (following_profile_key, _) = Pubkey::find_program_address(
+                    &[b"user_profile", follow.following.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(following_profile_key, counterpart_info.key(), SocialError::InvalidReclaimAccount);
+
+                if !counterpart_closed {
+                    let mut following_profile = Account::<UserProfile>::try_from(counterpart_info)?;
+                    following_profile.followers_count = following_profile
+                        .followers_count
+                        .checked_sub(1)
+                        .ok_or(SocialError::CounterUnderflow)?;
+                    following_profile.exit(ctx.program_id)?;
+                }
+                profile.following_count = profile
+                    .following_count
+                    .checked_sub(1)
+                    .ok_or(SocialError::CounterUnderflow)?;
+                let following = follow.following;
+                follow.close(user.clone())?;
+
+                emit_cpi!(Unfollowed {
+                    follower: user_key,
+                    following,
+                });
+            } else if discriminator == ImageChunk::DISCRIMINATOR {
+                // Chunks are closed without deserializing them, 9KB each would exhaust the heap
+                require_keys_eq!(chunk_post, counterpart_info.key(), SocialError::InvalidReclaimAccount);
+                require!(!counterpart_closed, SocialError::InvalidReclaimAccount);
+
+                let mut post = Account::<Post>::try_from(counterpart_info)?;
+                require_keys_eq!(post.author, user_key, SocialError::NotPostAuthor);
+                post.image_chunks.retain(|chunk| chunk != account_info.key);
+                post.image_finalized = false;
+                post.exit(ctx.program_id)?;
+                close_account(account_info, &user)?;
+            } else {
+                return err!(SocialError::InvalidReclaimAccount);
+            }
+        }
+
+        msg!("Reclaimed {} accounts for: {}", ctx.remaining_accounts.len() / 2, user_key);
+        Ok(())
+    }
+
+    // Close a user profile once its posts, follows, followers and username are gone. A tombstone
+    // keeps the post nonce for a profile created again later
+    pub fn close_user_profile(ctx: Context<CloseUserProfile>) -> Result<()> {
+        let tombstone = &mut ctx.accounts.profile_tombstone;
+        let clock = Clock::get()?;
+
+        tombstone.user = ctx.accounts.user.key();
+        tombstone.post_nonce = ctx.accounts.user_profile.post_nonce;
+        tombstone.closed_at = clock.unix_timestamp;
+        tombstone.bump = ctx.bumps.profile_tombstone;
+
+        // The profile will be closed and lamports returned
+        msg!("User profile closed for: {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Grant a verification badge
+    pub fn verify_user(ctx: Context<VerifyUser>) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+        let clock = Clock::get()?;
+
+        require!(!profile.verified, SocialError::AlreadyVerified);
+
+        profile.verified = true;
+        profile.verified_by = Some(ctx.accounts.verifier.key());
+        profile.verified_at = Some(clock.unix_timestamp);
+        profile.revocation_reason = None;
+
+        msg!("User {} verified by {}", profile.user, ctx.accounts.verifier.key());
+        Ok(())
+    }
+
+    // Revoke a verification badge
+    pub fn revoke_verification(ctx: Context<VerifyUser>, reason: u8) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+
+        require!(profile.verified, SocialError::NotVerified);
+
+        profile.verified = false;
+        profile.revocation_reason = Some(reason);
+
+        msg!("Verification of {} revoked by {}", profile.user, ctx.accounts.verifier.key());
+        Ok(())
+    }
+
     // Update user profile
+    #[allow(clippy::too_many_arguments)]
     pub fn update_user_profile(
         ctx: Context<UpdateUserProfile>,
-        username: Option<String>,
         display_name: Option<String>,
         bio: Option<String>,
         avatar_url: Option<String>,
         cover_image_url: Option<String>,
         website_url: Option<String>,
         location: Option<String>,
+        private: Option<bool>,
     ) -> Result<()> {
         let profile = &mut ctx.accounts.user_profile;
-
-        if let Some(username) = username {
-            require!(username.len() <= 50, SocialError::UsernameTooLong);
-            profile.username = Some(username);
-        }
+        let config = &ctx.accounts.config;
 
         if let Some(display_name) = display_name {
-            require!(display_name.len() <= 50, SocialError::DisplayNameTooLong);
+            require!(display_name.len() <= config.max_display_name_length as usize, SocialError::DisplayNameTooLong);
             profile.display_name = Some(display_name);
         }
 
         if let Some(bio) = bio {
-            require!(bio.len() <= 160, SocialError::BioTooLong);
+            require!(bio.len() <= config.max_bio_length as usize, SocialError::BioTooLong);
             profile.bio = Some(bio);
         }
 
         if let Some(avatar_url) = avatar_url {
-            require!(avatar_url.len() <= 200, SocialError::AvatarUrlTooLong);
+            require!(avatar_url.len() <= config.max_url_length as usize, SocialError::AvatarUrlTooLong);
             profile.avatar_url = Some(avatar_url);
         }
 
         if let Some(cover_image_url) = cover_image_url {
-            require!(cover_image_url.len() <= 200, SocialError::CoverImageUrlTooLong);
+            require!(cover_image_url.len() <= config.max_url_length as usize, SocialError::CoverImageUrlTooLong);
             profile.cover_image_url = Some(cover_image_url);
         }
 
         if let Some(website_url) = website_url {
-            require!(website_url.len() <= 200, SocialError::WebsiteUrlTooLong);
+            require!(website_url.len() <= config.max_url_length as usize, SocialError::WebsiteUrlTooLong);
             profile.website_url = Some(website_url);
         }
 
         if let Some(location) = location {
-            require!(location.len() <= 100, SocialError::LocationTooLong);
+            require!(location.len() <= config.max_location_length as usize, SocialError::LocationTooLong);
             profile.location = Some(location);
         }
 
+        if let Some(private) = private {
+            profile.private = private;
+        }
+
+        emit_cpi!(ProfileUpdated {
+            user: ctx.accounts.user.key(),
+            profile: profile.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         msg!("User profile updated for: {}", ctx.accounts.user.key());
         Ok(())
     }
+
+    // Rewrite a profile created before the fields appended after bump into the current layout,
+    // anyone may pay. Those profiles have no room for the new fields, and shortened strings can
+    // leave stale bytes after bump, so the legacy fields are decoded rather than zero-extended
+    pub fn migrate_user_profile(ctx: Context<MigrateUserProfile>) -> Result<()> {
+        let profile_info = ctx.accounts.user_profile.to_account_info();
+
+        let legacy = {
+            let data = profile_info.try_borrow_data()?;
+            require!(
+                data.len() == LEGACY_USER_PROFILE_SIZE && data[..8] == UserProfile::DISCRIMINATOR,
+                SocialError::ProfileNotLegacy
+            );
+            LegacyUserProfile::deserialize(&mut &data[8..])
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+        };
+
+        // Grow to the size initialize_user_profile allocates
+        let space = 8 + 32 + 1 + 4 + MAX_USERNAME_LENGTH + 1 + 4 + MAX_DISPLAY_NAME_LENGTH + 1 + 4 + MAX_BIO_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_LOCATION_LENGTH + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 1 + 32 + 1 + 8 + 1 + 1 + 1 + 4 + 32 * MAX_PINNED_POSTS;
+        let rent = Rent::get()?.minimum_balance(space).saturating_sub(profile_info.lamports());
+        if rent > 0 {
+            transfer_lamports(&ctx.accounts.payer.to_account_info(), &profile_info, rent)?;
+        }
+        profile_info.realloc(space, true)?;
+
+        let profile = UserProfile {
+            user: legacy.user,
+            username: legacy.username,
+            display_name: legacy.display_name,
+            bio: legacy.bio,
+            avatar_url: legacy.avatar_url,
+            cover_image_url: legacy.cover_image_url,
+            website_url: legacy.website_url,
+            location: legacy.location,
+            followers_count: legacy.followers_count,
+            following_count: legacy.following_count,
+            post_count: legacy.post_count,
+            created_at: legacy.created_at,
+            verified: legacy.verified,
+            bump: legacy.bump,
+            post_nonce: 0,
+            verified_by: None,
+            verified_at: None,
+            revocation_reason: None,
+            private: false,
+            pinned_posts: Vec::new(),
+        };
+        profile.try_serialize(&mut &mut profile_info.try_borrow_mut_data()?[..])?;
+
+        msg!("User profile migrated for: {}", profile.user);
+        Ok(())
+    }
+
+    // Pin one of the user's own posts to their profile
+    pub fn pin_post(ctx: Context<PinPost>) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+        let post_key = ctx.accounts.post.key();
+
+        require!(!profile.pinned_posts.contains(&post_key), SocialError::PostAlreadyPinned);
+        require!(profile.pinned_posts.len() < MAX_PINNED_POSTS, SocialError::TooManyPinnedPosts);
+        profile.pinned_posts.push(post_key);
+
+        msg!("Post {} pinned by: {}", post_key, ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Unpin a post, which may already be deleted
+    pub fn unpin_post(ctx: Context<UnpinPost>, post: Pubkey) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+
+        let index = profile
+            .pinned_posts
+            .iter()
+            .position(|pinned| *pinned == post)
+            .ok_or(SocialError::PostNotPinned)?;
+        profile.pinned_posts.remove(index);
+
+        msg!("Post {} unpinned by: {}", post, ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Claim a unique username, releasing the profile's current one
+    pub fn claim_username(ctx: Context<ClaimUsername>, username: String) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+        let record = &mut ctx.accounts.username_record;
+        let clock = Clock::get()?;
+
+        validate_username(&username, &ctx.accounts.config)?;
+
+        // An existing record can only be claimed again by its own profile, to change the casing
+        let existing_record = record.profile != Pubkey::default();
+        require!(
+            !existing_record || record.profile == profile.key(),
+            SocialError::UsernameAlreadyClaimed
+        );
+
+        // Release the current record in the same transaction
+        if let Some(current_username) = profile.username.as_ref() {
+            let current_record = ctx
+                .accounts
+                .current_username_record
+                .as_ref()
+                .ok_or(SocialError::UsernameRecordRequired)?;
+            let (current_record_key, _) = Pubkey::find_program_address(
+                &[b"username", username_seed(current_username).as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                current_record.key(),
+                current_record_key,
+                SocialError::UsernameRecordRequired
+            );
+
+            // Usernames set before the registry existed have no record to close, or one that
+            // another profile has since claimed, in which case the legacy name is just dropped.
+            // When the current name is the one being claimed, its record is the one initialized
+            // above, which is kept and has no discriminator to read until the instruction exits
+            if current_record.key() != record.key() && !current_record.data_is_empty() {
+                require_keys_eq!(*current_record.owner, crate::ID, SocialError::UsernameRecordRequired);
+                let current_profile =
+                    UsernameRecord::try_deserialize(&mut &current_record.try_borrow_data()?[..])?.profile;
+                if current_profile == profile.key() {
+                    close_account(current_record, &ctx.accounts.user.to_account_info())?;
+                }
+            }
+        }
+
+        record.username = username.clone();
+        record.owner = ctx.accounts.user.key();
+        record.profile = profile.key();
+        if !existing_record {
+            record.claimed_at = clock.unix_timestamp;
+        }
+        record.bump = ctx.bumps.username_record;
+
+        profile.username = Some(username);
+
+        msg!("Username claimed by: {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Release the profile's username so others can claim it
+    pub fn release_username(ctx: Context<ReleaseUsername>) -> Result<()> {
+        // The record will be closed and lamports returned
+        ctx.accounts.user_profile.username = None;
+
+        msg!("Username released by: {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Clear a username set before the registry existed. The profile's owner can always clear
+    // it, anyone can once another profile holds the record for that name
+    pub fn clear_legacy_username(ctx: Context<ClearLegacyUsername>) -> Result<()> {
+        let profile = &mut ctx.accounts.user_profile;
+        let username_record = &ctx.accounts.username_record;
+
+        let legacy_username = profile.username.as_ref().ok_or(SocialError::UsernameNotLegacy)?;
+        let (record_key, _) = Pubkey::find_program_address(
+            &[b"username", username_seed(legacy_username).as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(username_record.key(), record_key, SocialError::UsernameRecordRequired);
+
+        if username_record.data_is_empty() {
+            require_keys_eq!(ctx.accounts.authority.key(), profile.user, SocialError::NotProfileOwner);
+        } else {
+            require_keys_eq!(*username_record.owner, crate::ID, SocialError::UsernameRecordRequired);
+            let record_profile =
+                UsernameRecord::try_deserialize(&mut &username_record.try_borrow_data()?[..])?.profile;
+            require_keys_neq!(record_profile, profile.key(), SocialError::UsernameNotLegacy);
+        }
+
+        profile.username = None;
+
+        msg!("Legacy username cleared for: {}", profile.user);
+        Ok(())
+    }
+
+    // Transfer a username to another wallet's profile
+    pub fn transfer_username(ctx: Context<TransferUsername>) -> Result<()> {
+        let record = &mut ctx.accounts.username_record;
+        let from_profile = &mut ctx.accounts.from_profile;
+        let to_profile = &mut ctx.accounts.to_profile;
+        let clock = Clock::get()?;
+
+        require!(to_profile.username.is_none(), SocialError::ProfileAlreadyHasUsername);
+
+        record.owner = ctx.accounts.new_owner.key();
+        record.profile = to_profile.key();
+        record.claimed_at = clock.unix_timestamp;
+
+        to_profile.username = from_profile.username.take();
+
+        msg!("Username transferred to: {}", ctx.accounts.new_owner.key());
+        Ok(())
+    }
+}
+
+// Helper function for the fee schedule: exempt wallets pay nothing, verified profiles and
+// discount token holders get the larger of their discounts
+fn effective_fee_bps(
+    config: &Config,
+    fee_bps: u16,
+    profile: &UserProfile,
+    discount_token_account: Option<&TokenAccount>,
+) -> u16 {
+    if config.fee_exempt_wallets.contains(&profile.user) {
+        return 0;
+    }
+
+    let mut discount_bps = 0;
+    if profile.verified {
+        discount_bps = config.verified_fee_discount_bps;
+    }
+    if let (Some(mint), Some(token_account)) = (config.discount_mint, discount_token_account) {
+        if token_account.mint == mint
+            && token_account.owner == profile.user
+            && token_account.amount >= config.discount_min_balance
+        {
+            discount_bps = discount_bps.max(config.token_holder_discount_bps);
+        }
+    }
+
+    (fee_bps as u32 * (10_000 - discount_bps as u32) / 10_000) as u16
+}
+
+// Helper function for platform fees, charged as basis points of the post's rent
+fn collect_post_fee<'info>(
+    post: &AccountInfo<'info>,
+    author: &AccountInfo<'info>,
+    treasury: &mut Account<'info, Treasury>,
+    fee_bps: u16,
+) -> Result<u64> {
+    let platform_fee = (post.lamports() as u128 * fee_bps as u128 / 10_000) as u64;
+    if platform_fee > 0 {
+        transfer_lamports(author, &treasury.to_account_info(), platform_fee)?;
+        treasury.total_collected += platform_fee;
+    }
+    Ok(platform_fee)
+}
+
+// Helper function for lamport transfers
+fn transfer_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let ix = anchor_lang::solana_program::system_instruction::transfer(
+        from.key,
+        to.key,
+        amount,
+    );
+
+    anchor_lang::solana_program::program::invoke(
+        &ix,
+        &[from.clone(), to.clone()],
+    )?;
+
+    Ok(())
+}
+
+// Helper function for block checks, the blocker's BlockRelation for the actor must not exist
+fn require_not_blocked(
+    block_account: Option<&AccountInfo>,
+    blocker: &Pubkey,
+    actor: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let block_account = block_account.ok_or(SocialError::BlockAccountRequired)?;
+    let (block_key, _) = Pubkey::find_program_address(
+        &[b"block", blocker.as_ref(), actor.as_ref()],
+        program_id,
+    );
+    require_keys_eq!(block_account.key(), block_key, SocialError::BlockAccountRequired);
+    require!(block_account.data_is_empty(), SocialError::Blocked);
+    Ok(())
+}
+
+// Helper function for username rules: ASCII alphanumerics and underscore, no leading digit, not reserved
+fn validate_username(username: &str, config: &Config) -> Result<()> {
+    require!(username.len() >= 3, SocialError::UsernameTooShort);
+    require!(username.len() <= config.max_username_length as usize, SocialError::UsernameTooLong);
+    require!(
+        username.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_'),
+        SocialError::InvalidUsernameCharacters
+    );
+    require!(!username.as_bytes()[0].is_ascii_digit(), SocialError::UsernameStartsWithDigit);
+
+    let normalized = username.to_ascii_lowercase();
+    require!(
+        !config.reserved_usernames.contains(&normalized),
+        SocialError::UsernameReserved
+    );
+    Ok(())
+}
+
+// Helper function for username record seeds, usernames are unique case-insensitively
+fn username_seed(username: &str) -> [u8; 32] {
+    hash::hash(username.to_lowercase().as_bytes()).to_bytes()
+}
+
+// Helper function for closing accounts without deserializing them
+fn close_account<'info>(info: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **info.try_borrow_mut_lamports()? = 0;
+
+    info.assign(&anchor_lang::system_program::ID);
+    info.realloc(0, false)?;
+    Ok(())
 }
 
 // Account Structures
 
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub reserved_usernames: Vec<String>, // Lowercase, cannot be claimed
+    pub verifiers: Vec<Pubkey>, // Can set UserProfile.verified
+    pub paused: bool, // Rejects every mutating instruction except admin ones
+    pub max_content_length: u16,
+    pub max_username_length: u16,
+    pub max_display_name_length: u16,
+    pub max_bio_length: u16,
+    pub max_url_length: u16,
+    pub max_location_length: u16,
+    pub max_chunk_size: u16,
+    pub text_post_fee_bps: u16, // Share of a text post's rent charged as a fee, 0 = free
+    pub image_post_fee_bps: u16,
+    pub treasury_authority: Pubkey, // Can withdraw fees, e.g. a multisig
+    pub verified_fee_discount_bps: u16, // 10000 waives fees for verified profiles
+    pub token_holder_discount_bps: u16,
+    pub discount_mint: Option<Pubkey>, // Holding discount_min_balance of this token earns the discount
+    pub discount_min_balance: u64,
+    pub fee_exempt_wallets: Vec<Pubkey>, // Never charged platform fees
+    pub bump: u8,
+}
+
+#[account]
+pub struct Treasury {
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct Post {
+    // 1 = nonce-seeded layout. Posts created before this field have no version byte, offset 8
+    // is the first byte of author on those, so they are told apart by length instead: exactly
+    // LEGACY_POST_SIZE bytes, smaller than any current post. migrate_post rewrites them
+    pub version: u8,
     pub author: Pubkey,
+    pub root: Pubkey, // First post of the thread (itself if not a reply), at offset 41 for memcmp filters
     pub content: String,
     pub post_type: u8, // 0 = text, 1 = image
     pub image_chunks: Vec<Pubkey>, // References to image chunk accounts
     pub total_image_chunks: u8,
     pub reply_to: Option<Pubkey>,
+    pub quote_of: Option<Pubkey>, // Post being quoted, if any
+    pub timestamp: i64,
+    pub likes: u64,
+    pub reposts: u64,
+    pub replies: u64,
+    pub edited_at: Option<i64>, // Set on the most recent edit
+    pub edit_count: u16, // Number of PostRevision accounts for this post
+    pub image_finalized: bool, // No more chunks can be added once set
+    pub image_hash: [u8; 32], // SHA-256 of the concatenated chunk data
+    pub image_mime_type: String,
+    pub image_width: u32,
+    pub image_height: u32,
+    pub attachment_count: u8, // Number of Attachment accounts for this post
+    pub fee_paid: u64, // Platform fee charged when the post was created
+    pub reactions: [u64; REACTION_KINDS], // Count per reaction kind, heart mirrors likes
+    pub open_revisions: u16, // PostRevision accounts not yet closed by close_post_accounts
+    pub open_attachments: u8, // Attachment accounts not yet closed by close_post_accounts
+    pub bump: u8,
+}
+
+// Profile layout before the fields appended after bump, only read by migrate_user_profile
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyUserProfile {
+    pub user: Pubkey,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub cover_image_url: Option<String>,
+    pub website_url: Option<String>,
+    pub location: Option<String>,
+    pub followers_count: u64,
+    pub following_count: u64,
+    pub post_count: u64,
+    pub created_at: i64,
+    pub verified: bool,
+    pub bump: u8,
+}
+
+// Post layout before the version byte, only read by migrate_post
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyPost {
+    pub author: Pubkey,
+    pub content: String,
+    pub post_type: u8,
+    pub image_chunks: Vec<Pubkey>,
+    pub total_image_chunks: u8,
+    pub reply_to: Option<Pubkey>,
     pub timestamp: i64,
     pub likes: u64,
     pub reposts: u64,
@@ -247,6 +1945,42 @@ pub struct Post {
     pub bump: u8,
 }
 
+#[account]
+pub struct PostRevision {
+    pub post: Pubkey, // Reference to edited post
+    pub revision: u16,
+    pub content: String, // Content as it was before this edit
+    pub timestamp: i64, // When this content was replaced
+    pub bump: u8,
+}
+
+#[account]
+pub struct Attachment {
+    pub post: Pubkey, // Reference to parent post
+    pub index: u8,
+    pub kind: u8, // 0 = Arweave tx id, 1 = IPFS CID, 2 = HTTPS URL, 3 = on-chain image chunks
+    pub uri: String,
+    pub content_hash: [u8; 32], // SHA-256 of the referenced content
+    pub mime_type: String,
+    pub bump: u8,
+}
+
+#[account]
+pub struct PostTombstone {
+    pub post: Pubkey, // Address of the deleted post
+    pub author: Pubkey,
+    pub deleted_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct ProfileTombstone {
+    pub user: Pubkey,
+    pub post_nonce: u64, // Restored if the profile is created again
+    pub closed_at: i64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct ImageChunk {
     pub post: Pubkey, // Reference to parent post
@@ -272,6 +2006,23 @@ pub struct UserProfile {
     pub created_at: i64,
     pub verified: bool,  // For verification badges
     pub bump: u8,
+    // Fields below are appended after bump. Profiles created before them are exactly
+    // LEGACY_USER_PROFILE_SIZE bytes and must go through migrate_user_profile first
+    pub post_nonce: u64, // Seeds post PDAs, never decremented
+    pub verified_by: Option<Pubkey>, // Verifier that granted the badge
+    pub verified_at: Option<i64>,
+    pub revocation_reason: Option<u8>, // 0 = unspecified, 1 = impersonation, 2 = policy violation, 3 = requested by user
+    pub private: bool, // Follows go through FollowRequest approval
+    pub pinned_posts: Vec<Pubkey>, // Own posts shown first on the profile, oldest pin first
+}
+
+#[account]
+pub struct UsernameRecord {
+    pub username: String, // As claimed, lookups are case-insensitive
+    pub owner: Pubkey,
+    pub profile: Pubkey,
+    pub claimed_at: i64,
+    pub bump: u8,
 }
 
 #[account]
@@ -282,6 +2033,30 @@ pub struct FollowRelation {
     pub bump: u8,
 }
 
+#[account]
+pub struct FollowRequest {
+    pub requester: Pubkey,
+    pub target: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct BlockRelation {
+    pub blocker: Pubkey,
+    pub blocked: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct MuteRelation {
+    pub muter: Pubkey,
+    pub muted: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct LikeRelation {
     pub user: Pubkey,
@@ -290,23 +2065,98 @@ pub struct LikeRelation {
     pub bump: u8,
 }
 
+#[account]
+pub struct ReactionRelation {
+    pub user: Pubkey,
+    pub post: Pubkey,
+    pub reaction: u8, // Index into Post.reactions, never the heart
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct RepostRelation {
+    pub user: Pubkey,
+    pub post: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Bookmark {
+    pub user: Pubkey,
+    pub post: Pubkey,
+    pub folder: Option<u8>, // Client-defined folder or tag
+    pub created_at: i64,
+    pub bump: u8,
+}
+
 // Context Structures
 
 #[derive(Accounts)]
-pub struct Initialize {}
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 32 + 4 + (4 + MAX_USERNAME_LENGTH) * 32 + 4 + 32 * 10 + 1 + 2 * 7 + 2 + 2 + 32 + 2 + 2 + 1 + 32 + 8 + 4 + 32 * 10 + 1, // Discriminator + admin + reserved usernames + verifiers + paused + limits + fee rates + treasury_authority + fee schedule + bump
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 8 + 8 + 1, // Discriminator + total_collected + total_withdrawn + bump
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, program::Solcials>,
+
+    // Only the upgrade authority can create the config
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SocialError::NotAdmin)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ SocialError::NotAdmin
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(content: String, timestamp: i64)]
 pub struct CreateTextPost<'info> {
     #[account(
         init,
         payer = author,
-        space = 8 + 32 + 4 + 280 + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1, // Discriminator + author + content + post_type + empty chunks + total_chunks + reply_to + counters + bump
-        seeds = [b"post", author.key().as_ref(), &timestamp.to_le_bytes()],
+        space = 8 + 1 + 32 + 32 + 4 + MAX_CONTENT_LENGTH + 1 + 4 + 1 + 1 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 32 + 4 + 32 + 4 + 4 + 1 + 8 + 8 * REACTION_KINDS + 2 + 1 + 1, // Discriminator + version + author + root + content + post_type + empty chunks + total_chunks + reply_to + quote_of + counters + edited_at + edit_count + image metadata + attachment_count + fee_paid + reactions + open revisions and attachments + bump
+        seeds = [b"post", author.key().as_ref(), &user_profile.post_nonce.to_le_bytes()],
         bump
     )]
     pub post: Account<'info, Post>,
 
+    // Required when replying, must match reply_to
+    #[account(mut)]
+    pub parent_post: Option<Account<'info, Post>>,
+
+    /// CHECK: Parent author's BlockRelation for the author, required when replying and must not exist
+    pub parent_block_account: Option<UncheckedAccount<'info>>,
+
     #[account(
         mut,
         seeds = [b"user_profile", author.key().as_ref()],
@@ -314,23 +2164,48 @@ pub struct CreateTextPost<'info> {
     )]
     pub user_profile: Account<'info, UserProfile>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    // Proves the author holds the configured discount token
+    #[account(constraint = discount_token_account.owner == author.key() @ SocialError::InvalidDiscountTokenAccount)]
+    pub discount_token_account: Option<Account<'info, TokenAccount>>,
+
     #[account(mut)]
     pub author: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(content: String, timestamp: i64)]
 pub struct CreateImagePost<'info> {
     #[account(
         init,
         payer = author,
-        space = 8 + 32 + 4 + 280 + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1, // Same as text post initially
-        seeds = [b"post", author.key().as_ref(), &timestamp.to_le_bytes()],
+        space = 8 + 1 + 32 + 32 + 4 + MAX_CONTENT_LENGTH + 1 + 4 + 1 + 1 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 32 + 4 + 32 + 4 + 4 + 1 + 8 + 8 * REACTION_KINDS + 2 + 1 + 1, // Same as text post initially, add_image_chunk grows image_chunks
+        seeds = [b"post", author.key().as_ref(), &user_profile.post_nonce.to_le_bytes()],
         bump
     )]
     pub post: Account<'info, Post>,
 
+    // Required when replying, must match reply_to
+    #[account(mut)]
+    pub parent_post: Option<Account<'info, Post>>,
+
+    /// CHECK: Parent author's BlockRelation for the author, required when replying and must not exist
+    pub parent_block_account: Option<UncheckedAccount<'info>>,
+
     #[account(
         mut,
         seeds = [b"user_profile", author.key().as_ref()],
@@ -338,45 +2213,273 @@ pub struct CreateImagePost<'info> {
     )]
     pub user_profile: Account<'info, UserProfile>,
 
-    #[account(mut)]
-    pub author: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
 
-#[derive(Accounts)]
-#[instruction(chunk_data: Vec<u8>, chunk_index: u8)]
-pub struct AddImageChunk<'info> {
     #[account(
-        init,
-        payer = author,
-        space = 8 + 32 + 1 + 1 + 4 + 9216 + 1, // Discriminator + post + chunk_index + total_chunks + data_len + data + bump
-        seeds = [b"chunk", post.key().as_ref(), &chunk_index.to_le_bytes()],
-        bump
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
     )]
-    pub image_chunk: Account<'info, ImageChunk>,
+    pub treasury: Account<'info, Treasury>,
 
-    #[account(mut)]
-    pub post: Account<'info, Post>,
+    // Proves the author holds the configured discount token
+    #[account(constraint = discount_token_account.owner == author.key() @ SocialError::InvalidDiscountTokenAccount)]
+    pub discount_token_account: Option<Account<'info, TokenAccount>>,
 
     #[account(mut)]
     pub author: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct FollowUser<'info> {
+pub struct QuotePost<'info> {
     #[account(
         init,
-        payer = follower,
-        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
-        seeds = [b"follow", follower.key().as_ref(), following.key().as_ref()],
+        payer = author,
+        space = 8 + 1 + 32 + 32 + 4 + MAX_CONTENT_LENGTH + 1 + 4 + 1 + 1 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 32 + 4 + 32 + 4 + 4 + 1 + 8 + 8 * REACTION_KINDS + 2 + 1 + 1, // Same as text post
+        seeds = [b"post", author.key().as_ref(), &user_profile.post_nonce.to_le_bytes()],
         bump
     )]
-    pub follow_account: Account<'info, FollowRelation>,
+    pub post: Account<'info, Post>,
+
+    pub quoted_post: Account<'info, Post>,
 
     #[account(
         mut,
-        seeds = [b"user_profile", follower.key().as_ref()],
+        seeds = [b"user_profile", author.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    // Proves the author holds the configured discount token
+    #[account(constraint = discount_token_account.owner == author.key() @ SocialError::InvalidDiscountTokenAccount)]
+    pub discount_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(chunk_data: Vec<u8>, chunk_index: u8)]
+pub struct AddImageChunk<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = 8 + 32 + 1 + 1 + 4 + MAX_CHUNK_SIZE + 1, // Discriminator + post + chunk_index + total_chunks + data_len + data + bump
+        seeds = [b"chunk", post.key().as_ref(), &chunk_index.to_le_bytes()],
+        bump
+    )]
+    pub image_chunk: Account<'info, ImageChunk>,
+
+    #[account(
+        mut,
+        has_one = author @ SocialError::NotPostAuthor,
+        constraint = post.post_type == 1 @ SocialError::NotImagePost,
+        constraint = !post.image_finalized @ SocialError::ImageAlreadyFinalized,
+        realloc = post.to_account_info().data_len() + 32, // Room for one more image_chunks entry
+        realloc::payer = author,
+        realloc::zero = false
+    )]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeImagePost<'info> {
+    #[account(
+        mut,
+        has_one = author @ SocialError::NotPostAuthor,
+        constraint = post.post_type == 1 @ SocialError::NotImagePost,
+        constraint = !post.image_finalized @ SocialError::ImageAlreadyFinalized
+    )]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddAttachment<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = 8 + 32 + 1 + 1 + 4 + MAX_URL_LENGTH + 32 + 4 + 32 + 1, // Discriminator + post + index + kind + uri + content_hash + mime_type + bump
+        seeds = [b"attachment", post.key().as_ref(), &post.attachment_count.to_le_bytes()],
+        bump
+    )]
+    pub attachment: Account<'info, Attachment>,
+
+    #[account(mut, has_one = author @ SocialError::NotPostAuthor)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EditPost<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = 8 + 32 + 2 + 4 + MAX_CONTENT_LENGTH + 8 + 1, // Discriminator + post + revision + previous content + timestamp + bump
+        seeds = [b"revision", post.key().as_ref(), &post.edit_count.to_le_bytes()],
+        bump
+    )]
+    pub revision: Account<'info, PostRevision>,
+
+    #[account(mut, has_one = author @ SocialError::NotPostAuthor)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeletePost<'info> {
+    #[account(
+        mut,
+        close = author,
+        has_one = author @ SocialError::NotPostAuthor
+    )]
+    pub post: Account<'info, Post>,
+
+    // The post's reply_to, omitted for top-level posts or once the parent is deleted
+    #[account(mut)]
+    pub parent_post: Option<Account<'info, Post>>,
+
+    #[account(
+        init,
+        payer = author,
+        space = 8 + 32 + 32 + 8 + 1, // Discriminator + post + author + deleted_at + bump
+        seeds = [b"tombstone", post.key().as_ref()],
+        bump
+    )]
+    pub tombstone: Option<Account<'info, PostTombstone>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", author.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePostAccounts<'info> {
+    #[account(mut, has_one = author @ SocialError::NotPostAuthor)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePost<'info> {
+    /// CHECK: Legacy post, layout and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub post: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FollowUser<'info> {
+    #[account(
+        init,
+        payer = follower,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"follow", follower.key().as_ref(), following.key().as_ref()],
+        bump
+    )]
+    pub follow_account: Account<'info, FollowRelation>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", follower.key().as_ref()],
         bump = follower_profile.bump
     )]
     pub follower_profile: Account<'info, UserProfile>,
@@ -384,17 +2487,180 @@ pub struct FollowUser<'info> {
     #[account(
         mut,
         seeds = [b"user_profile", following.key().as_ref()],
-        bump = following_profile.bump
+        bump = following_profile.bump,
+        constraint = following_profile.user == following.key() @ SocialError::FollowingProfileMismatch,
+        constraint = !following_profile.private @ SocialError::ProfilePrivate
     )]
     pub following_profile: Account<'info, UserProfile>,
 
+    /// CHECK: Followed user's BlockRelation for the follower, must not exist
+    #[account(
+        seeds = [b"block", following.key().as_ref(), follower.key().as_ref()],
+        bump,
+        constraint = block_account.data_is_empty() @ SocialError::Blocked
+    )]
+    pub block_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub follower: Signer<'info>,
-    /// CHECK: This is safe because we're only using it as a seed
+    /// CHECK: Only used as a seed, must own following_profile
+    #[account(constraint = following.key() != follower.key() @ SocialError::CannotFollowSelf)]
     pub following: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RequestFollow<'info> {
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"follow_request", requester.key().as_ref(), target.key().as_ref()],
+        bump
+    )]
+    pub follow_request: Account<'info, FollowRequest>,
+
+    #[account(
+        seeds = [b"user_profile", target.key().as_ref()],
+        bump = target_profile.bump,
+        constraint = target_profile.user == target.key() @ SocialError::FollowingProfileMismatch,
+        constraint = target_profile.private @ SocialError::ProfileNotPrivate
+    )]
+    pub target_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Requester must not already follow the target
+    #[account(
+        seeds = [b"follow", requester.key().as_ref(), target.key().as_ref()],
+        bump,
+        constraint = follow_account.data_is_empty() @ SocialError::AlreadyFollowing
+    )]
+    pub follow_account: UncheckedAccount<'info>,
+
+    /// CHECK: Target's BlockRelation for the requester, must not exist
+    #[account(
+        seeds = [b"block", target.key().as_ref(), requester.key().as_ref()],
+        bump,
+        constraint = block_account.data_is_empty() @ SocialError::Blocked
+    )]
+    pub block_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    /// CHECK: Only used as a seed, must own target_profile
+    #[account(constraint = target.key() != requester.key() @ SocialError::CannotFollowSelf)]
+    pub target: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApproveFollowRequest<'info> {
+    #[account(
+        mut,
+        close = target,
+        seeds = [b"follow_request", requester.key().as_ref(), target.key().as_ref()],
+        bump = follow_request.bump
+    )]
+    pub follow_request: Account<'info, FollowRequest>,
+
+    #[account(
+        init,
+        payer = target,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"follow", requester.key().as_ref(), target.key().as_ref()],
+        bump
+    )]
+    pub follow_account: Account<'info, FollowRelation>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", requester.key().as_ref()],
+        bump = follower_profile.bump
+    )]
+    pub follower_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", target.key().as_ref()],
+        bump = following_profile.bump
+    )]
+    pub following_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub target: Signer<'info>,
+    /// CHECK: This is safe because we're only using it as a seed
+    pub requester: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RejectFollowRequest<'info> {
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"follow_request", requester.key().as_ref(), target.key().as_ref()],
+        bump = follow_request.bump
+    )]
+    pub follow_request: Account<'info, FollowRequest>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub target: Signer<'info>,
+    /// CHECK: Only used as a seed and to refund the request's rent
+    #[account(mut)]
+    pub requester: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelFollowRequest<'info> {
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"follow_request", requester.key().as_ref(), target.key().as_ref()],
+        bump = follow_request.bump
+    )]
+    pub follow_request: Account<'info, FollowRequest>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    /// CHECK: This is safe because we're only using it as a seed
+    pub target: AccountInfo<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UnfollowUser<'info> {
     #[account(
@@ -419,12 +2685,20 @@ pub struct UnfollowUser<'info> {
     )]
     pub following_profile: Account<'info, UserProfile>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub follower: Signer<'info>,
     /// CHECK: This is safe because we're only using it as a seed
     pub following: AccountInfo<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct LikePost<'info> {
     #[account(
@@ -439,11 +2713,27 @@ pub struct LikePost<'info> {
     #[account(mut)]
     pub post: Account<'info, Post>,
 
+    /// CHECK: Post author's BlockRelation for the user, must not exist
+    #[account(
+        seeds = [b"block", post.author.as_ref(), user.key().as_ref()],
+        bump,
+        constraint = block_account.data_is_empty() @ SocialError::Blocked
+    )]
+    pub block_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UnlikePost<'info> {
     #[account(
@@ -457,27 +2747,420 @@ pub struct UnlikePost<'info> {
     #[account(mut)]
     pub post: Account<'info, Post>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct InitializeUserProfile<'info> {
+#[instruction(reaction: u8)]
+pub struct ReactToPost<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + 32 + 4 + 50 + 4 + 50 + 4 + 160 + 4 + 200 + 4 + 200 + 4 + 200 + 4 + 100 + 8 + 8 + 8 + 8 + 1 + 1, // Discriminator + pubkey + all optional strings with length prefixes + counters + verified + bump
-        seeds = [b"user_profile", user.key().as_ref()],
+        space = 8 + 32 + 32 + 1 + 8 + 1, // Account discriminator + 2 pubkeys + reaction + timestamp + bump
+        seeds = [b"reaction", user.key().as_ref(), post.key().as_ref(), &[reaction]],
         bump
     )]
-    pub user_profile: Account<'info, UserProfile>,
+    pub reaction_account: Account<'info, ReactionRelation>,
 
     #[account(mut)]
-    pub user: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub post: Account<'info, Post>,
 
-#[derive(Accounts)]
+    /// CHECK: Post author's BlockRelation for the user, must not exist
+    #[account(
+        seeds = [b"block", post.author.as_ref(), user.key().as_ref()],
+        bump,
+        constraint = block_account.data_is_empty() @ SocialError::Blocked
+    )]
+    pub block_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(reaction: u8)]
+pub struct RemoveReaction<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"reaction", user.key().as_ref(), post.key().as_ref(), &[reaction]],
+        bump = reaction_account.bump
+    )]
+    pub reaction_account: Account<'info, ReactionRelation>,
+
+    #[account(mut)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BlockUser<'info> {
+    #[account(
+        init,
+        payer = blocker,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"block", blocker.key().as_ref(), blocked.key().as_ref()],
+        bump
+    )]
+    pub block_account: Account<'info, BlockRelation>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", blocker.key().as_ref()],
+        bump = blocker_profile.bump
+    )]
+    pub blocker_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Blocked user's profile, updated in the handler if it exists
+    #[account(
+        mut,
+        seeds = [b"user_profile", blocked.key().as_ref()],
+        bump
+    )]
+    pub blocked_profile: UncheckedAccount<'info>,
+
+    /// CHECK: Blocker's follow of the blocked user, closed in the handler if it exists
+    #[account(
+        mut,
+        seeds = [b"follow", blocker.key().as_ref(), blocked.key().as_ref()],
+        bump
+    )]
+    pub blocker_follow: UncheckedAccount<'info>,
+
+    /// CHECK: Blocked user's follow of the blocker, closed in the handler if it exists
+    #[account(
+        mut,
+        seeds = [b"follow", blocked.key().as_ref(), blocker.key().as_ref()],
+        bump
+    )]
+    pub blocked_follow: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub blocker: Signer<'info>,
+    /// CHECK: Only used as a seed and to refund the rent of their follow
+    #[account(
+        mut,
+        constraint = blocked.key() != blocker.key() @ SocialError::CannotBlockSelf
+    )]
+    pub blocked: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnblockUser<'info> {
+    #[account(
+        mut,
+        close = blocker,
+        seeds = [b"block", blocker.key().as_ref(), blocked.key().as_ref()],
+        bump = block_account.bump
+    )]
+    pub block_account: Account<'info, BlockRelation>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub blocker: Signer<'info>,
+    /// CHECK: This is safe because we're only using it as a seed
+    pub blocked: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MuteUser<'info> {
+    #[account(
+        init,
+        payer = muter,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"mute", muter.key().as_ref(), muted.key().as_ref()],
+        bump
+    )]
+    pub mute_account: Account<'info, MuteRelation>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub muter: Signer<'info>,
+    /// CHECK: This is safe because we're only using it as a seed
+    pub muted: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnmuteUser<'info> {
+    #[account(
+        mut,
+        close = muter,
+        seeds = [b"mute", muter.key().as_ref(), muted.key().as_ref()],
+        bump = mute_account.bump
+    )]
+    pub mute_account: Account<'info, MuteRelation>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub muter: Signer<'info>,
+    /// CHECK: This is safe because we're only using it as a seed
+    pub muted: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Repost<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 1, // Account discriminator + 2 pubkeys + timestamp + bump
+        seeds = [b"repost", user.key().as_ref(), post.key().as_ref()],
+        bump
+    )]
+    pub repost_account: Account<'info, RepostRelation>,
+
+    #[account(mut)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UndoRepost<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"repost", user.key().as_ref(), post.key().as_ref()],
+        bump = repost_account.bump
+    )]
+    pub repost_account: Account<'info, RepostRelation>,
+
+    #[account(mut)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BookmarkPost<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 1 + 1 + 8 + 1, // Account discriminator + 2 pubkeys + optional folder + created_at + bump
+        seeds = [b"bookmark", user.key().as_ref(), post.key().as_ref()],
+        bump
+    )]
+    pub bookmark: Account<'info, Bookmark>,
+
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveBookmark<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"bookmark", user.key().as_ref(), post.key().as_ref()],
+        bump = bookmark.bump
+    )]
+    pub bookmark: Account<'info, Bookmark>,
+
+    /// CHECK: Only used as a seed, the post may already be deleted
+    pub post: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeUserProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 1 + 4 + MAX_USERNAME_LENGTH + 1 + 4 + MAX_DISPLAY_NAME_LENGTH + 1 + 4 + MAX_BIO_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_URL_LENGTH + 1 + 4 + MAX_LOCATION_LENGTH + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 1 + 32 + 1 + 8 + 1 + 1 + 1 + 4 + 32 * MAX_PINNED_POSTS, // Discriminator + pubkey + all optional strings with option tags and length prefixes + counters + verified + bump + post_nonce + verification details + private + pinned posts
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Left by close_user_profile, may not exist. Restored and closed in the handler
+    #[account(
+        mut,
+        seeds = [b"profile_tombstone", user.key().as_ref()],
+        bump
+    )]
+    pub profile_tombstone: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimAccounts<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserProfile<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.post_count == 0 @ SocialError::OutstandingPosts,
+        constraint = user_profile.following_count == 0 @ SocialError::OutstandingFollows,
+        // Followers' relations would outlive the count, blocking a follower removes their follow
+        constraint = user_profile.followers_count == 0 @ SocialError::OutstandingFollowers,
+        constraint = user_profile.username.is_none() @ SocialError::UsernameStillClaimed
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 8 + 8 + 1, // Discriminator + user + post_nonce + closed_at + bump
+        seeds = [b"profile_tombstone", user.key().as_ref()],
+        bump
+    )]
+    pub profile_tombstone: Account<'info, ProfileTombstone>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyUser<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused,
+        constraint = config.verifiers.contains(&verifier.key()) @ SocialError::NotVerifier
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", user_profile.user.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub verifier: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
 pub struct UpdateUserProfile<'info> {
     #[account(
         mut,
@@ -486,34 +3169,489 @@ pub struct UpdateUserProfile<'info> {
     )]
     pub user_profile: Account<'info, UserProfile>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     pub user: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateUserProfile<'info> {
+    /// CHECK: Legacy profile, layout and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub user_profile: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PinPost<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(constraint = post.author == user_profile.user @ SocialError::NotPostAuthor)]
+    pub post: Account<'info, Post>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnpinPost<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub user: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = treasury_authority @ SocialError::NotTreasuryAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Any wallet chosen by the treasury authority
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub treasury_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(username: String)]
+pub struct ClaimUsername<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 4 + MAX_USERNAME_LENGTH + 32 + 32 + 8 + 1, // Discriminator + username + owner + profile + claimed_at + bump
+        seeds = [b"username", username_seed(&username).as_ref()],
+        bump
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Record of the profile's current username, required when it has one. Verified and closed in the handler
+    #[account(mut)]
+    pub current_username_record: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseUsername<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"username", username_seed(&username_record.username).as_ref()],
+        bump = username_record.bump,
+        constraint = username_record.profile == user_profile.key() @ SocialError::UsernameNotOwned
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClearLegacyUsername<'info> {
+    #[account(mut)]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Registry record for the profile's username, may not exist. Verified in the handler
+    pub username_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferUsername<'info> {
+    #[account(
+        mut,
+        seeds = [b"username", username_seed(&username_record.username).as_ref()],
+        bump = username_record.bump,
+        constraint = username_record.profile == from_profile.key() @ SocialError::UsernameNotOwned
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = from_profile.bump
+    )]
+    pub from_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", new_owner.key().as_ref()],
+        bump = to_profile.bump
+    )]
+    pub to_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ SocialError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub owner: Signer<'info>,
+    pub new_owner: Signer<'info>, // Must accept the username
+}
+
+// Events
+
+#[event]
+pub struct PostCreated {
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub root: Pubkey,
+    pub post_type: u8,
+    pub reply_to: Option<Pubkey>,
+    pub quote_of: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PostEdited {
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub revision: Pubkey, // PostRevision holding the replaced content
+    pub edit_count: u16,
+    pub edited_at: i64,
+}
+
+#[event]
+pub struct PostDeleted {
+    pub post: Pubkey,
+    pub author: Pubkey,
+    pub tombstone: bool,
+}
+
+#[event]
+pub struct ImageChunkAdded {
+    pub post: Pubkey,
+    pub chunk: Pubkey,
+    pub chunk_index: u8,
+    pub total_chunks: u8,
+}
+
+#[event]
+pub struct Liked {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub likes: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Unliked {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub likes: u64,
+}
+
+#[event]
+pub struct Reacted {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub reaction: u8,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReactionRemoved {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub reaction: u8,
+    pub count: u64,
+}
+
+#[event]
+pub struct Followed {
+    pub follower: Pubkey,
+    pub following: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Unfollowed {
+    pub follower: Pubkey,
+    pub following: Pubkey,
+}
+
+#[event]
+pub struct ProfileUpdated {
+    pub user: Pubkey,
+    pub profile: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeCollected {
+    pub payer: Pubkey,
+    pub post: Pubkey,
+    pub amount: u64,
+    pub fee_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 // Custom Errors
 #[error_code]
 pub enum SocialError {
-    #[msg("Content cannot be longer than 280 characters")]
+    #[msg("Content exceeds the maximum length")]
     ContentTooLong,
     #[msg("Content cannot be empty")]
     ContentEmpty,
-    #[msg("Username cannot be longer than 50 characters")]
+    #[msg("Username exceeds the maximum length")]
     UsernameTooLong,
-    #[msg("Bio cannot be longer than 160 characters")]
+    #[msg("Bio exceeds the maximum length")]
     BioTooLong,
-    #[msg("Avatar URL cannot be longer than 200 characters")]
+    #[msg("Avatar URL exceeds the maximum length")]
     AvatarUrlTooLong,
-    #[msg("Display Name cannot be longer than 50 characters")]
+    #[msg("Display Name exceeds the maximum length")]
     DisplayNameTooLong,
-    #[msg("Location cannot be longer than 100 characters")]
+    #[msg("Location exceeds the maximum length")]
     LocationTooLong,
-    #[msg("Website URL cannot be longer than 200 characters")]
+    #[msg("Website URL exceeds the maximum length")]
     WebsiteUrlTooLong,
-    #[msg("Cover Image URL cannot be longer than 200 characters")]
+    #[msg("Cover Image URL exceeds the maximum length")]
     CoverImageUrlTooLong,
     #[msg("Image arrays must have the same length")]
     ImageArraysMismatch,
     #[msg("Too many images")]
     TooManyImages,
-    #[msg("Chunk size cannot exceed 9KB")]
+    #[msg("Chunk size exceeds the maximum")]
     ChunkTooLarge,
+    #[msg("Only the post author can perform this action")]
+    NotPostAuthor,
+    #[msg("Post has reached the maximum number of edits")]
+    TooManyEdits,
+    #[msg("All image chunk and revision accounts of the post must be provided")]
+    IncompletePostAccounts,
+    #[msg("Image chunk does not belong to this post")]
+    InvalidImageChunk,
+    #[msg("Revision does not belong to this post")]
+    InvalidRevision,
+    #[msg("Reply parent must be provided and match reply_to")]
+    InvalidReplyParent,
+    #[msg("Image chunks can only be added to image posts")]
+    NotImagePost,
+    #[msg("Chunk index must be less than the total number of chunks")]
+    ChunkIndexOutOfRange,
+    #[msg("Total chunks cannot change between chunks of the same post")]
+    TotalChunksMismatch,
+    #[msg("Image post has already been finalized")]
+    ImageAlreadyFinalized,
+    #[msg("Every image chunk must be uploaded and provided in order")]
+    IncompleteImage,
+    #[msg("MIME type cannot be longer than 32 characters")]
+    MimeTypeTooLong,
+    #[msg("MIME type must be an image type")]
+    InvalidMimeType,
+    #[msg("Image width and height must be greater than zero")]
+    InvalidImageDimensions,
+    #[msg("A post cannot have more than 4 attachments")]
+    TooManyAttachments,
+    #[msg("Unknown attachment storage kind")]
+    InvalidAttachmentKind,
+    #[msg("Attachment URI cannot be longer than 200 characters")]
+    AttachmentUriTooLong,
+    #[msg("Attachment URI is not valid for its storage kind")]
+    InvalidAttachmentUri,
+    #[msg("Attachment MIME type must be of the form type/subtype")]
+    InvalidAttachmentMimeType,
+    #[msg("Attachment does not belong to this post")]
+    InvalidAttachment,
+    #[msg("Counter cannot go below zero")]
+    CounterUnderflow,
+    #[msg("The profile's current username record must be provided")]
+    UsernameRecordRequired,
+    #[msg("Username is not owned by this profile")]
+    UsernameNotOwned,
+    #[msg("Profile already has a username")]
+    ProfileAlreadyHasUsername,
+    #[msg("Username is registered to this profile, use release_username")]
+    UsernameNotLegacy,
+    #[msg("Only the profile owner can clear an unregistered username")]
+    NotProfileOwner,
+    #[msg("Username must be at least 3 characters")]
+    UsernameTooShort,
+    #[msg("Username can only contain ASCII letters, digits and underscores")]
+    InvalidUsernameCharacters,
+    #[msg("Username cannot start with a digit")]
+    UsernameStartsWithDigit,
+    #[msg("Username is reserved")]
+    UsernameReserved,
+    #[msg("Too many reserved usernames")]
+    TooManyReservedUsernames,
+    #[msg("Username is not reserved")]
+    ReservedUsernameNotFound,
+    #[msg("Only the program admin can perform this action")]
+    NotAdmin,
+    #[msg("Only a verifier can perform this action")]
+    NotVerifier,
+    #[msg("Wallet is already a verifier")]
+    VerifierAlreadyExists,
+    #[msg("Wallet is not a verifier")]
+    VerifierNotFound,
+    #[msg("Too many verifiers")]
+    TooManyVerifiers,
+    #[msg("User is already verified")]
+    AlreadyVerified,
+    #[msg("User is not verified")]
+    NotVerified,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Limit must be greater than zero and within the account size")]
+    InvalidLimit,
+    #[msg("Fee rate cannot exceed 10000 basis points")]
+    InvalidFeeRate,
+    #[msg("Only the treasury authority can perform this action")]
+    NotTreasuryAuthority,
+    #[msg("Treasury balance is too low for this withdrawal")]
+    InsufficientTreasuryBalance,
+    #[msg("Wallet is already fee exempt")]
+    WalletAlreadyFeeExempt,
+    #[msg("Wallet is not fee exempt")]
+    WalletNotFeeExempt,
+    #[msg("Too many fee exempt wallets")]
+    TooManyFeeExemptWallets,
+    #[msg("Discount token account must belong to the author")]
+    InvalidDiscountTokenAccount,
+    #[msg("Account cannot be reclaimed by this user")]
+    InvalidReclaimAccount,
+    #[msg("All posts must be deleted before closing the profile")]
+    OutstandingPosts,
+    #[msg("All follows must be reclaimed before closing the profile")]
+    OutstandingFollows,
+    #[msg("Username must be released before closing the profile")]
+    UsernameStillClaimed,
+    #[msg("Users cannot follow themselves")]
+    CannotFollowSelf,
+    #[msg("Followed profile does not belong to the followed wallet")]
+    FollowingProfileMismatch,
+    #[msg("You have been blocked by this user")]
+    Blocked,
+    #[msg("The block relation account for this action must be provided")]
+    BlockAccountRequired,
+    #[msg("Users cannot block themselves")]
+    CannotBlockSelf,
+    #[msg("This profile is private, send a follow request instead")]
+    ProfilePrivate,
+    #[msg("This profile is public and can be followed directly")]
+    ProfileNotPrivate,
+    #[msg("You already follow this user")]
+    AlreadyFollowing,
+    #[msg("Unknown reaction kind")]
+    InvalidReaction,
+    #[msg("Use like_post for the heart reaction")]
+    UseLikeForHeart,
+    #[msg("Post is already pinned")]
+    PostAlreadyPinned,
+    #[msg("Too many pinned posts")]
+    TooManyPinnedPosts,
+    #[msg("Post is not pinned")]
+    PostNotPinned,
+    #[msg("Followers must be removed before closing the profile")]
+    OutstandingFollowers,
+    #[msg("Account is not an image chunk, revision or attachment of this post")]
+    InvalidPostAccount,
+    #[msg("Post is not in the legacy layout")]
+    PostNotLegacy,
+    #[msg("Image has more chunks than finalize_image_post can accept")]
+    TooManyChunks,
+    #[msg("Profile is not in the legacy layout")]
+    ProfileNotLegacy,
+    #[msg("Username is already claimed by another profile")]
+    UsernameAlreadyClaimed,
 }
\ No newline at end of file
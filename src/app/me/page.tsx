@@ -152,15 +152,20 @@ export default function ProfilePage() {
       }
 
       const solcialsProgram = new SolcialsCustomProgramService(connection);
+      const wallet = {
+        publicKey,
+        signTransaction,
+        connected
+      };
+
+      // Usernames live in the on-chain registry, so a changed one is claimed separately
+      if (editForm.username && editForm.username !== profile?.username) {
+        await solcialsProgram.claimUsername(wallet, editForm.username);
+      }
       
       // Update profile with form data using the real Solana program
       await solcialsProgram.updateUserProfile(
-        {
-          publicKey,
-          signTransaction,
-          connected
-        },
-        editForm.username || undefined,
+        wallet,
         editForm.displayName || undefined,
         editForm.bio || undefined,
         editForm.websiteUrl || undefined,
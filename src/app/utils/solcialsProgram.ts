@@ -5,6 +5,7 @@ import {
   TransactionInstruction,
   SystemProgram,
   AccountInfo,
+  AccountMeta,
 } from '@solana/web3.js';
 import { SocialPost } from '../types/social';
 import { getProgramId } from './networkConfig';
@@ -21,10 +22,27 @@ const INSTRUCTION_DISCRIMINATORS = {
   followUser: Buffer.from(sha256.digest("global:follow_user")).slice(0, 8),
   likePost: Buffer.from(sha256.digest("global:like_post")).slice(0, 8),
   updateUserProfile: Buffer.from(sha256.digest("global:update_user_profile")).slice(0, 8),
+  claimUsername: Buffer.from(sha256.digest("global:claim_username")).slice(0, 8),
   unfollowUser: Buffer.from(sha256.digest("global:unfollow_user")).slice(0, 8),
   unlikePost: Buffer.from(sha256.digest("global:unlike_post")).slice(0, 8),
+  migratePost: Buffer.from(sha256.digest("global:migrate_post")).slice(0, 8),
+  migrateUserProfile: Buffer.from(sha256.digest("global:migrate_user_profile")).slice(0, 8),
 };
 
+// Anchor account discriminator for Post (8-byte SHA256 hash of "account:Post")
+const POST_DISCRIMINATOR = Buffer.from(sha256.digest("account:Post")).slice(0, 8);
+
+// Posts created before Post.version have no version byte and are exactly this long,
+// migratePost rewrites them into the current layout
+const LEGACY_POST_SIZE = 8 + 32 + 4 + 280 + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1; // 395 bytes
+
+// Profiles created before the fields appended after UserProfile.bump are exactly this long,
+// migrateUserProfile rewrites them into the current layout
+const LEGACY_USER_PROFILE_SIZE = 8 + 32 + 4 + 50 + 4 + 50 + 4 + 160 + 4 + 200 + 4 + 200 + 4 + 200 + 4 + 100 + 8 + 8 + 8 + 8 + 1 + 1; // 1062 bytes
+
+// Size create_text_post and create_image_post allocate for a post
+const POST_ACCOUNT_SIZE = 8 + 1 + 32 + 32 + 4 + 280 + 1 + 4 + 1 + 1 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 2 + 1 + 32 + 4 + 32 + 4 + 4 + 1 + 8 + 8 * 6 + 2 + 1 + 1;
+
 interface WalletAdapter {
   publicKey: PublicKey | null;
   signTransaction?: <T extends Transaction>(transaction: T) => Promise<T>;
@@ -33,6 +51,7 @@ interface WalletAdapter {
 
 // TypeScript interfaces matching your Rust structs
 export interface CustomPost {
+  version: number; // 1 = nonce-seeded layout, 0 for legacy posts, which have no version byte and are detected by length (LEGACY_POST_SIZE)
   author: PublicKey;
   content: string;
   postType: number; // 0 = text, 1 = image
@@ -59,6 +78,7 @@ export interface CustomUserProfile {
   createdAt: number;
   verified: boolean;
   bump: number;
+  postNonce: bigint; // Seeds the author's next post PDA
 }
 
 export class SolcialsCustomProgramService {
@@ -231,21 +251,63 @@ export class SolcialsCustomProgramService {
   }
 
   // Derive PDA for post account
-  private getPostPDA(author: PublicKey, timestamp: number): [PublicKey, number] {
-    // Encode timestamp exactly as Rust does: timestamp.to_le_bytes() for i64
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp));
+  private getPostPDA(author: PublicKey, postNonce: bigint): [PublicKey, number] {
+    // Encode the nonce exactly as Rust does: post_nonce.to_le_bytes() for u64
+    const nonceBuffer = Buffer.alloc(8);
+    nonceBuffer.writeBigUInt64LE(postNonce);
     
     return PublicKey.findProgramAddressSync(
       [
         Buffer.from('post'),
         author.toBuffer(),
-        timestampBuffer  // Use the same encoding as in instruction data
+        nonceBuffer
       ],
       this.programId
     );
   }
 
+  // Derive PDA for the author's next post from the nonce stored on their profile
+  private async getNextPostPDA(author: PublicKey): Promise<PublicKey> {
+    const profile = await this.getUserProfile(author);
+    if (!profile) {
+      throw new Error('User profile not found');
+    }
+    const [postPDA] = this.getPostPDA(author, profile.postNonce);
+    return postPDA;
+  }
+
+  // Derive PDA for the program config
+  private getConfigPDA(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('config')],
+      this.programId
+    );
+  }
+
+  // Derive PDA Anchor's emit_cpi! signs event self-invocations with
+  private getEventAuthorityPDA(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('__event_authority')],
+      this.programId
+    );
+  }
+
+  // Derive PDA for the fee treasury
+  private getTreasuryPDA(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('treasury')],
+      this.programId
+    );
+  }
+
+  // Derive PDA for the tombstone close_user_profile leaves behind
+  private getProfileTombstonePDA(user: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('profile_tombstone'), user.toBuffer()],
+      this.programId
+    );
+  }
+
   // Derive PDA for user profile
   private getUserProfilePDA(user: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
@@ -254,6 +316,14 @@ export class SolcialsCustomProgramService {
     );
   }
 
+  // Derive PDA for a username record, seeded by the SHA-256 of the lowercased name
+  private getUsernameRecordPDA(username: string): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('username'), Buffer.from(sha256.digest(username.toLowerCase()))],
+      this.programId
+    );
+  }
+
   // Derive PDA for follow relationship
   private getFollowPDA(follower: PublicKey, following: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
@@ -278,6 +348,63 @@ export class SolcialsCustomProgramService {
     );
   }
 
+  // Derive PDA for block relationship, it exists when blocker has blocked blocked
+  private getBlockPDA(blocker: PublicKey, blocked: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('block'),
+        blocker.toBuffer(),
+        blocked.toBuffer()
+      ],
+      this.programId
+    );
+  }
+
+  // Read a post's author, which follows the discriminator and version byte
+  private async getPostAuthor(postPubkey: PublicKey): Promise<PublicKey> {
+    const postAccount = await this.connection.getAccountInfo(postPubkey);
+    if (!postAccount) {
+      throw new Error('Post not found');
+    }
+    if (postAccount.data.length === LEGACY_POST_SIZE) {
+      throw new Error('Post is in the legacy layout and must be migrated first');
+    }
+    return new PublicKey(postAccount.data.slice(8 + 1, 8 + 1 + 32));
+  }
+
+  // Accounts for create_text_post and create_image_post. Optional accounts that are left out
+  // are passed as the program ID, which Anchor reads as None
+  private async getCreatePostKeys(
+    author: PublicKey,
+    postPDA: PublicKey,
+    replyTo?: PublicKey
+  ): Promise<AccountMeta[]> {
+    const [userProfilePDA] = this.getUserProfilePDA(author);
+    const [configPDA] = this.getConfigPDA();
+    const [treasuryPDA] = this.getTreasuryPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
+
+    let parentBlockPDA = this.programId;
+    if (replyTo) {
+      const parentAuthor = await this.getPostAuthor(replyTo);
+      [parentBlockPDA] = this.getBlockPDA(parentAuthor, author);
+    }
+
+    return [
+      { pubkey: postPDA, isSigner: false, isWritable: true }, // post
+      { pubkey: replyTo ?? this.programId, isSigner: false, isWritable: !!replyTo }, // parent_post
+      { pubkey: parentBlockPDA, isSigner: false, isWritable: false }, // parent_block_account
+      { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
+      { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+      { pubkey: treasuryPDA, isSigner: false, isWritable: true }, // treasury
+      { pubkey: this.programId, isSigner: false, isWritable: false }, // discount_token_account
+      { pubkey: author, isSigner: true, isWritable: true }, // author
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+      { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+      { pubkey: this.programId, isSigner: false, isWritable: false }, // program
+    ];
+  }
+
   // Initialize user profile (first time setup)
   async initializeUserProfile(wallet: WalletAdapter): Promise<string> {
     if (!wallet.publicKey || !wallet.signTransaction || !wallet.connected) {
@@ -285,6 +412,8 @@ export class SolcialsCustomProgramService {
     }
 
     const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
+    const [profileTombstonePDA] = this.getProfileTombstonePDA(wallet.publicKey);
+    const [configPDA] = this.getConfigPDA();
 
     // Check if profile already exists
     const existingProfile = await this.connection.getAccountInfo(userProfilePDA);
@@ -294,9 +423,11 @@ export class SolcialsCustomProgramService {
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile (first)
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // user (signer, second)
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program (third)
+        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
+        { pubkey: profileTombstonePDA, isSigner: false, isWritable: true }, // profile_tombstone
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // user
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.initializeUserProfile,
@@ -330,37 +461,15 @@ export class SolcialsCustomProgramService {
       throw new Error('Content too long (max 280 characters)');
     }
 
-    const timestamp = Math.floor(Date.now() / 1000);
-    const [postPDA] = this.getPostPDA(wallet.publicKey, timestamp);
-    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
-
-    console.log('🕐 Timestamp debugging:');
-    console.log('  Raw timestamp (seconds):', timestamp);
-    console.log('  Timestamp as Date:', new Date(timestamp * 1000).toISOString());
-    console.log('  Timestamp BigInt:', BigInt(timestamp));
-    
-    // Show how we encode it for PDA vs instruction
-    const pdaTimestampBuffer = Buffer.alloc(8);
-    pdaTimestampBuffer.writeBigInt64LE(BigInt(timestamp));
-    console.log('  PDA timestamp buffer:', pdaTimestampBuffer.toString('hex'));
-    console.log('  Post PDA:', postPDA.toString());
-    
-    // Check if post account already exists (collision detection)
-    const existingPostAccount = await this.connection.getAccountInfo(postPDA);
-    if (existingPostAccount) {
-      console.warn('⚠️ Post account already exists, adjusting timestamp to avoid collision');
-      // Wait 1 second and try again with new timestamp
-      await new Promise(resolve => setTimeout(resolve, 1000));
-      return this.createTextPost(wallet, content, replyTo);
-    }
-
-    // Ensure user profile exists
+    // Ensure user profile exists, the post PDA is seeded by its post nonce
     await this.ensureUserProfile(wallet);
 
+    const postPDA = await this.getNextPostPDA(wallet.publicKey);
+    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
+
     // Check wallet balance (only need to cover rent exemption now - no platform fees!)
     const walletBalance = await this.connection.getBalance(wallet.publicKey);
-    const ACCOUNT_SIZE = 8 + 32 + 4 + 280 + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1; // 395 bytes
-    const rentExemption = await this.connection.getMinimumBalanceForRentExemption(ACCOUNT_SIZE);
+    const rentExemption = await this.connection.getMinimumBalanceForRentExemption(POST_ACCOUNT_SIZE);
     const estimatedTxFee = 10000; // Conservative estimate for transaction fee
     const totalNeeded = rentExemption + estimatedTxFee; // No platform fees!
 
@@ -382,7 +491,7 @@ export class SolcialsCustomProgramService {
     console.log('  🔧 System Program:', SystemProgram.programId.toString());
 
     // Create instruction data
-    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + 8 + (replyTo ? 1 + 32 : 1));
+    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + (replyTo ? 1 + 32 : 1));
     let offset = 0;
     
     // Instruction discriminator for create_text_post
@@ -395,12 +504,6 @@ export class SolcialsCustomProgramService {
     instructionData.write(content, offset, 'utf8');
     offset += Buffer.byteLength(content, 'utf8');
     
-    // Timestamp (i64, little endian)
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp));
-    timestampBuffer.copy(instructionData, offset);
-    offset += 8;
-    
     // Reply to (optional)
     if (replyTo) {
       instructionData.writeUInt8(1, offset); // Some discriminator
@@ -411,12 +514,7 @@ export class SolcialsCustomProgramService {
     }
 
     const instruction = new TransactionInstruction({
-      keys: [
-        { pubkey: postPDA, isSigner: false, isWritable: true },        // post
-        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // author
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
-      ],
+      keys: await this.getCreatePostKeys(wallet.publicKey, postPDA, replyTo),
       programId: this.programId,
       data: instructionData,
     });
@@ -452,31 +550,15 @@ export class SolcialsCustomProgramService {
       throw new Error('Content too long (max 280 characters)');
     }
 
-    const timestamp = Math.floor(Date.now() / 1000);
-    const [postPDA] = this.getPostPDA(wallet.publicKey, timestamp);
-    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
-
-    console.log('🕐 Image post timestamp debugging:');
-    console.log('  Raw timestamp (seconds):', timestamp);
-    console.log('  Timestamp as Date:', new Date(timestamp * 1000).toISOString());
-    console.log('  Post PDA:', postPDA.toString());
-    
-    // Check if post account already exists (collision detection)
-    const existingPostAccount = await this.connection.getAccountInfo(postPDA);
-    if (existingPostAccount) {
-      console.warn('⚠️ Post account already exists, adjusting timestamp to avoid collision');
-      // Wait 1 second and try again with new timestamp
-      await new Promise(resolve => setTimeout(resolve, 1000));
-      return this.createImagePost(wallet, content, replyTo);
-    }
-
-    // Ensure user profile exists
+    // Ensure user profile exists, the post PDA is seeded by its post nonce
     await this.ensureUserProfile(wallet);
 
+    const postPDA = await this.getNextPostPDA(wallet.publicKey);
+    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
+
     // Check wallet balance (only need to cover rent exemption now - no platform fees!)
     const walletBalance = await this.connection.getBalance(wallet.publicKey);
-    const ACCOUNT_SIZE = 8 + 32 + 4 + 280 + 1 + 4 + 1 + 32 + 8 + 8 + 8 + 8 + 1; // 395 bytes - same as text post
-    const rentExemption = await this.connection.getMinimumBalanceForRentExemption(ACCOUNT_SIZE);
+    const rentExemption = await this.connection.getMinimumBalanceForRentExemption(POST_ACCOUNT_SIZE);
     const estimatedTxFee = 10000; // Conservative estimate for transaction fee
     const totalNeeded = rentExemption + estimatedTxFee; // No platform fees!
 
@@ -499,7 +581,7 @@ export class SolcialsCustomProgramService {
     console.log('  🎯 Program ID:', this.programId.toString());
 
     // Create instruction data (similar to text post)
-    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + 8 + (replyTo ? 1 + 32 : 1));
+    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + (replyTo ? 1 + 32 : 1));
     let offset = 0;
     
     // Instruction discriminator for create_image_post
@@ -512,12 +594,6 @@ export class SolcialsCustomProgramService {
     instructionData.write(content, offset, 'utf8');
     offset += Buffer.byteLength(content, 'utf8');
     
-    // Timestamp (i64, little endian)
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp));
-    timestampBuffer.copy(instructionData, offset);
-    offset += 8;
-    
     // Reply to (optional)
     if (replyTo) {
       instructionData.writeUInt8(1, offset); // Some discriminator
@@ -528,12 +604,7 @@ export class SolcialsCustomProgramService {
     }
 
     const instruction = new TransactionInstruction({
-      keys: [
-        { pubkey: postPDA, isSigner: false, isWritable: true },        // post
-        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // author
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
-      ],
+      keys: await this.getCreatePostKeys(wallet.publicKey, postPDA, replyTo),
       programId: this.programId,
       data: instructionData,
     });
@@ -564,6 +635,9 @@ export class SolcialsCustomProgramService {
     const [followPDA] = this.getFollowPDA(wallet.publicKey, targetUser);
     const [followerProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
     const [followingProfilePDA] = this.getUserProfilePDA(targetUser);
+    const [blockPDA] = this.getBlockPDA(targetUser, wallet.publicKey);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     // Ensure your own profile exists
     await this.ensureUserProfile(wallet);
@@ -576,12 +650,16 @@ export class SolcialsCustomProgramService {
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: followPDA, isSigner: false, isWritable: true },
-        { pubkey: followerProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: followingProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
-        { pubkey: targetUser, isSigner: false, isWritable: false },
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: followPDA, isSigner: false, isWritable: true }, // follow_account
+        { pubkey: followerProfilePDA, isSigner: false, isWritable: true }, // follower_profile
+        { pubkey: followingProfilePDA, isSigner: false, isWritable: true }, // following_profile
+        { pubkey: blockPDA, isSigner: false, isWritable: false }, // block_account
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // follower
+        { pubkey: targetUser, isSigner: false, isWritable: false }, // following
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.followUser,
@@ -609,13 +687,21 @@ export class SolcialsCustomProgramService {
     }
 
     const [likePDA] = this.getLikePDA(wallet.publicKey, postPubkey);
+    const postAuthor = await this.getPostAuthor(postPubkey);
+    const [blockPDA] = this.getBlockPDA(postAuthor, wallet.publicKey);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: likePDA, isSigner: false, isWritable: true },
-        { pubkey: postPubkey, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: likePDA, isSigner: false, isWritable: true }, // like_account
+        { pubkey: postPubkey, isSigner: false, isWritable: true }, // post
+        { pubkey: blockPDA, isSigner: false, isWritable: false }, // block_account
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // user
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.likePost,
@@ -715,21 +801,26 @@ export class SolcialsCustomProgramService {
           
           // Check if this is a post account by checking discriminator
           // Skip discriminator (8 bytes) and start parsing Post struct
-          if (data.length < 8) continue;
+          if (data.length < 8 || !data.slice(0, 8).equals(POST_DISCRIMINATOR)) continue;
           
           let offset = 8; // Skip discriminator
           
-          // Parse Post struct fields (UPDATED for new contract structure):
+          // Parse Post struct fields:
+          // pub version: u8 (1 byte, missing on legacy posts, so those are detected by length)
           // pub author: Pubkey (32 bytes)
+          // pub root: Pubkey (32 bytes, version 1 only)
           // pub content: String (4 bytes length + content)
           // pub post_type: u8 (1 byte)
-          // pub image_nft: Option<Pubkey> (1 byte discriminator + optional 32 bytes)
+          // pub image_chunks: Vec<Pubkey> (4 bytes length + 32 bytes each)
+          // pub total_image_chunks: u8 (1 byte)
           // pub reply_to: Option<Pubkey> (1 byte discriminator + optional 32 bytes)
+          // pub quote_of: Option<Pubkey> (1 byte discriminator + optional 32 bytes, version 1 only)
           // pub timestamp: i64 (8 bytes)
           // pub likes: u64 (8 bytes)
           // pub reposts: u64 (8 bytes) 
           // pub replies: u64 (8 bytes)
-          // pub bump: u8 (1 byte)
+          const version = data.length === LEGACY_POST_SIZE ? 0 : data.readUInt8(offset);
+          if (version > 0) offset += 1;
 
           if (data.length < offset + 32) continue;
 
@@ -738,6 +829,9 @@ export class SolcialsCustomProgramService {
           const author = new PublicKey(authorBytes);
           offset += 32;
 
+          // Root (32 bytes)
+          if (version > 0) offset += 32;
+
           // Content length (4 bytes)
           if (data.length < offset + 4) continue;
           const contentLength = data.readUInt32LE(offset);
@@ -792,6 +886,12 @@ export class SolcialsCustomProgramService {
             offset += 32;
           }
 
+          // Quote of option (1 byte discriminator + optional 32 bytes)
+          if (version > 0) {
+            if (data.length < offset + 1) continue;
+            offset += data.readUInt8(offset) === 1 ? 1 + 32 : 1;
+          }
+
           // Timestamp (8 bytes)
           if (data.length < offset + 8) continue;
           const timestamp = data.readBigInt64LE(offset);
@@ -1127,14 +1227,19 @@ export class SolcialsCustomProgramService {
     const [followPDA] = this.getFollowPDA(wallet.publicKey, targetUser);
     const [followerProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
     const [followingProfilePDA] = this.getUserProfilePDA(targetUser);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: followPDA, isSigner: false, isWritable: true },
-        { pubkey: followerProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: followingProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
-        { pubkey: targetUser, isSigner: false, isWritable: false },
+        { pubkey: followPDA, isSigner: false, isWritable: true }, // follow_account
+        { pubkey: followerProfilePDA, isSigner: false, isWritable: true }, // follower_profile
+        { pubkey: followingProfilePDA, isSigner: false, isWritable: true }, // following_profile
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // follower
+        { pubkey: targetUser, isSigner: false, isWritable: false }, // following
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.unfollowUser,
@@ -1158,12 +1263,17 @@ export class SolcialsCustomProgramService {
     }
 
     const [likePDA] = this.getLikePDA(wallet.publicKey, postPubkey);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: likePDA, isSigner: false, isWritable: true },
-        { pubkey: postPubkey, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
+        { pubkey: likePDA, isSigner: false, isWritable: true }, // like_account
+        { pubkey: postPubkey, isSigner: false, isWritable: true }, // post
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // user
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.unlikePost,
@@ -1180,6 +1290,81 @@ export class SolcialsCustomProgramService {
     return signature;
   }
 
+  // Rewrite a legacy post into the current layout, the wallet pays the extra rent
+  async migratePost(wallet: WalletAdapter, postPubkey: PublicKey): Promise<string> {
+    if (!wallet.publicKey || !wallet.signTransaction || !wallet.connected) {
+      throw new Error('Wallet not connected');
+    }
+
+    const postAccount = await this.connection.getAccountInfo(postPubkey);
+    if (!postAccount || postAccount.data.length !== LEGACY_POST_SIZE) {
+      throw new Error('Post is not in the legacy layout');
+    }
+
+    const [configPDA] = this.getConfigPDA();
+
+    const instruction = new TransactionInstruction({
+      keys: [
+        { pubkey: postPubkey, isSigner: false, isWritable: true }, // post
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // payer
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+      ],
+      programId: this.programId,
+      data: INSTRUCTION_DISCRIMINATORS.migratePost,
+    });
+
+    const transaction = new Transaction().add(instruction);
+    transaction.recentBlockhash = (await this.connection.getLatestBlockhash()).blockhash;
+    transaction.feePayer = wallet.publicKey;
+
+    const signedTransaction = await wallet.signTransaction(transaction);
+    const signature = await this.connection.sendRawTransaction(signedTransaction.serialize());
+
+    console.log('✅ Post migrated:', signature);
+
+    // Clear cache since the post layout changed
+    this.clearAccountsCache();
+
+    return signature;
+  }
+
+  // Rewrite a legacy user profile into the current layout, the wallet pays the extra rent
+  async migrateUserProfile(wallet: WalletAdapter, userPubkey: PublicKey): Promise<string> {
+    if (!wallet.publicKey || !wallet.signTransaction || !wallet.connected) {
+      throw new Error('Wallet not connected');
+    }
+
+    const [userProfilePDA] = this.getUserProfilePDA(userPubkey);
+    const profileAccount = await this.connection.getAccountInfo(userProfilePDA);
+    if (!profileAccount || profileAccount.data.length !== LEGACY_USER_PROFILE_SIZE) {
+      throw new Error('User profile is not in the legacy layout');
+    }
+
+    const [configPDA] = this.getConfigPDA();
+
+    const instruction = new TransactionInstruction({
+      keys: [
+        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // payer
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+      ],
+      programId: this.programId,
+      data: INSTRUCTION_DISCRIMINATORS.migrateUserProfile,
+    });
+
+    const transaction = new Transaction().add(instruction);
+    transaction.recentBlockhash = (await this.connection.getLatestBlockhash()).blockhash;
+    transaction.feePayer = wallet.publicKey;
+
+    const signedTransaction = await wallet.signTransaction(transaction);
+    const signature = await this.connection.sendRawTransaction(signedTransaction.serialize());
+
+    console.log('✅ User profile migrated:', signature);
+    return signature;
+  }
+
   // Get user profile
   async getUserProfile(userPubkey: PublicKey): Promise<CustomUserProfile | null> {
     return this.retryRequest(async () => {
@@ -1271,6 +1456,11 @@ export class SolcialsCustomProgramService {
         offset += 1;
         
         const bump = data.readUInt8(offset);
+        offset += 1;
+
+        // post_nonce: u64 (8 bytes), appended after bump. Legacy profiles have none, and may
+        // hold stale bytes there until migrateUserProfile rewrites them
+        const postNonce = data.length === LEGACY_USER_PROFILE_SIZE ? BigInt(0) : dataBuffer.readBigUInt64LE(offset);
 
         const profileData = {
           user,
@@ -1284,7 +1474,8 @@ export class SolcialsCustomProgramService {
           postCount,
           createdAt,
           verified,
-          bump
+          bump,
+          postNonce
         };
 
         console.log('🔍 Parsed user profile:', profileData);
@@ -1298,30 +1489,22 @@ export class SolcialsCustomProgramService {
     });
   }
 
-  // Update user profile
+  // Update user profile, usernames are changed through claimUsername
   async updateUserProfile(
     wallet: WalletAdapter,
-    username?: string,
     displayName?: string,
     bio?: string,
     websiteUrl?: string,
-    location?: string
+    location?: string,
+    isPrivate?: boolean
   ): Promise<string> {
     if (!wallet.publicKey || !wallet.signTransaction || !wallet.connected) {
       throw new Error('Wallet not connected');
     }
 
-    // Check username availability if username is being updated
-    if (username) {
-      const usernameCheck = await this.isUsernameAvailable(username, wallet.publicKey);
-      if (!usernameCheck.available) {
-        throw new Error(`Username not available: ${usernameCheck.message}`);
-      }
-      
-      console.log('✅ Username availability verified:', usernameCheck.message);
-    }
-
     const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     // Check if profile exists
     const existingProfile = await this.connection.getAccountInfo(userProfilePDA);
@@ -1334,21 +1517,24 @@ export class SolcialsCustomProgramService {
     // The Rust program still expects avatar_url and cover_image_url, so we send them as None
     const instructionData = Buffer.concat([
       INSTRUCTION_DISCRIMINATORS.updateUserProfile,
-      // Encode optional strings in the order the Rust program expects:
-      // username, display_name, bio, avatar_url, cover_image_url, website_url, location
-      this.encodeOptionalString(username),
+      // Encode the options in the order the Rust program expects:
+      // display_name, bio, avatar_url, cover_image_url, website_url, location, private
       this.encodeOptionalString(displayName),
       this.encodeOptionalString(bio),
       this.encodeOptionalString(undefined), // avatar_url as None
       this.encodeOptionalString(undefined), // cover_image_url as None
       this.encodeOptionalString(websiteUrl),
       this.encodeOptionalString(location),
+      this.encodeOptionalBool(isPrivate),
     ]);
 
     const instruction = new TransactionInstruction({
       keys: [
-        { pubkey: userProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: false },
+        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: false }, // user
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: instructionData,
@@ -1361,7 +1547,7 @@ export class SolcialsCustomProgramService {
     const signedTransaction = await wallet.signTransaction(transaction);
     const signature = await this.connection.sendRawTransaction(signedTransaction.serialize());
 
-    console.log('✅ User profile updated with unique username:', signature);
+    console.log('✅ User profile updated:', signature);
     
     // Clear cache since we added new data
     this.clearAccountsCache();
@@ -1369,6 +1555,65 @@ export class SolcialsCustomProgramService {
     return signature;
   }
 
+  // Claim a username in the on-chain registry, releasing the profile's current one. Claiming
+  // the current name with different casing just changes its casing
+  async claimUsername(wallet: WalletAdapter, username: string): Promise<string> {
+    if (!wallet.publicKey || !wallet.signTransaction || !wallet.connected) {
+      throw new Error('Wallet not connected');
+    }
+
+    // Check username availability before paying for the record
+    const usernameCheck = await this.isUsernameAvailable(username, wallet.publicKey);
+    if (!usernameCheck.available) {
+      throw new Error(`Username not available: ${usernameCheck.message}`);
+    }
+
+    console.log('✅ Username availability verified:', usernameCheck.message);
+
+    await this.ensureUserProfile(wallet);
+
+    const [usernameRecordPDA] = this.getUsernameRecordPDA(username);
+    const [configPDA] = this.getConfigPDA();
+    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
+
+    // The current name's record is closed in the same transaction, None when there is no name
+    const profile = await this.getUserProfile(wallet.publicKey);
+    const currentUsernameRecordPDA = profile?.username
+      ? this.getUsernameRecordPDA(profile.username)[0]
+      : this.programId;
+
+    const usernameBytes = Buffer.from(username, 'utf8');
+    const usernameLength = Buffer.alloc(4);
+    usernameLength.writeUInt32LE(usernameBytes.length, 0);
+
+    const instruction = new TransactionInstruction({
+      keys: [
+        { pubkey: usernameRecordPDA, isSigner: false, isWritable: true }, // username_record
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
+        { pubkey: currentUsernameRecordPDA, isSigner: false, isWritable: !!profile?.username }, // current_username_record
+        { pubkey: userProfilePDA, isSigner: false, isWritable: true }, // user_profile
+        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // user
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
+      ],
+      programId: this.programId,
+      data: Buffer.concat([INSTRUCTION_DISCRIMINATORS.claimUsername, usernameLength, usernameBytes]),
+    });
+
+    const transaction = new Transaction().add(instruction);
+    transaction.recentBlockhash = (await this.connection.getLatestBlockhash()).blockhash;
+    transaction.feePayer = wallet.publicKey;
+
+    const signedTransaction = await wallet.signTransaction(transaction);
+    const signature = await this.connection.sendRawTransaction(signedTransaction.serialize());
+
+    console.log('✅ Username claimed:', signature);
+
+    // Clear cache since the profile changed
+    this.clearAccountsCache();
+
+    return signature;
+  }
+
   // Helper method to encode optional strings for Solana program
   private encodeOptionalString(value?: string): Buffer {
     // Treat empty strings and undefined/null as None
@@ -1389,6 +1634,14 @@ export class SolcialsCustomProgramService {
     ]);
   }
 
+  // Helper method to encode optional booleans, undefined leaves the field unchanged
+  private encodeOptionalBool(value?: boolean): Buffer {
+    if (value === undefined) {
+      return Buffer.from([0]);
+    }
+    return Buffer.from([1, value ? 1 : 0]);
+  }
+
   // Calculate actual costs for different post types
   async calculatePostCosts(hasImage: boolean, imageSize?: number): Promise<{ totalCost: number; breakdown: string }> {
     try {
@@ -1456,8 +1709,8 @@ export class SolcialsCustomProgramService {
   }
 
   // Encode create text post instruction
-  private encodeCreateTextPostInstruction(content: string, timestamp: number, replyTo?: PublicKey): Buffer {
-    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + 8 + (replyTo ? 1 + 32 : 1));
+  private encodeCreateTextPostInstruction(content: string, replyTo?: PublicKey): Buffer {
+    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + (replyTo ? 1 + 32 : 1));
     let offset = 0;
     
     // Instruction discriminator for create_text_post
@@ -1470,12 +1723,6 @@ export class SolcialsCustomProgramService {
     instructionData.write(content, offset, 'utf8');
     offset += Buffer.byteLength(content, 'utf8');
     
-    // Timestamp (i64, little endian)
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp));
-    timestampBuffer.copy(instructionData, offset);
-    offset += 8;
-    
     // Reply to (optional)
     if (replyTo) {
       instructionData.writeUInt8(1, offset); // Some discriminator
@@ -1489,8 +1736,8 @@ export class SolcialsCustomProgramService {
   }
 
   // Encode create image post instruction
-  private encodeCreateImagePostInstruction(content: string, timestamp: number, replyTo?: PublicKey): Buffer {
-    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + 8 + (replyTo ? 1 + 32 : 1));
+  private encodeCreateImagePostInstruction(content: string, replyTo?: PublicKey): Buffer {
+    const instructionData = Buffer.alloc(8 + 4 + Buffer.byteLength(content, 'utf8') + (replyTo ? 1 + 32 : 1));
     let offset = 0;
     
     // Instruction discriminator for create_image_post
@@ -1503,12 +1750,6 @@ export class SolcialsCustomProgramService {
     instructionData.write(content, offset, 'utf8');
     offset += Buffer.byteLength(content, 'utf8');
     
-    // Timestamp (i64, little endian)
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp));
-    timestampBuffer.copy(instructionData, offset);
-    offset += 8;
-    
     // Reply to (optional)
     if (replyTo) {
       instructionData.writeUInt8(1, offset); // Some discriminator
@@ -1533,26 +1774,17 @@ export class SolcialsCustomProgramService {
 
     await this.ensureUserProfile(wallet);
 
-    const timestamp = Math.floor(Date.now() / 1000);
     const replyToPublicKey = new PublicKey(replyToPostId);
 
     // Create post PDA
-    const [postPda] = this.getPostPDA(wallet.publicKey, timestamp);
-
-    // Get user profile PDA
-    const [userProfilePda] = this.getUserProfilePDA(wallet.publicKey);
+    const postPda = await this.getNextPostPDA(wallet.publicKey);
 
     const transaction = new Transaction();
 
     const instruction = new TransactionInstruction({
       programId: this.programId,
-      keys: [
-        { pubkey: postPda, isSigner: false, isWritable: true },
-        { pubkey: userProfilePda, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
-      ],
-      data: this.encodeCreateTextPostInstruction(content, timestamp, replyToPublicKey),
+      keys: await this.getCreatePostKeys(wallet.publicKey, postPda, replyToPublicKey),
+      data: this.encodeCreateTextPostInstruction(content, replyToPublicKey),
     });
 
     transaction.add(instruction);
@@ -1579,26 +1811,17 @@ export class SolcialsCustomProgramService {
 
     await this.ensureUserProfile(wallet);
 
-    const timestamp = Math.floor(Date.now() / 1000);
     const replyToPublicKey = new PublicKey(replyToPostId);
 
     // Create post PDA
-    const [postPda] = this.getPostPDA(wallet.publicKey, timestamp);
-
-    // Get user profile PDA
-    const [userProfilePda] = this.getUserProfilePDA(wallet.publicKey);
+    const postPda = await this.getNextPostPDA(wallet.publicKey);
 
     const transaction = new Transaction();
 
     const instruction = new TransactionInstruction({
       programId: this.programId,
-      keys: [
-        { pubkey: postPda, isSigner: false, isWritable: true },
-        { pubkey: userProfilePda, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // author
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
-      ],
-      data: this.encodeCreateImagePostInstruction(content, timestamp, replyToPublicKey),
+      keys: await this.getCreatePostKeys(wallet.publicKey, postPda, replyToPublicKey),
+      data: this.encodeCreateImagePostInstruction(content, replyToPublicKey),
     });
 
     transaction.add(instruction);
@@ -1999,14 +2222,19 @@ export class SolcialsCustomProgramService {
 
     const [followerProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
     const [followingProfilePDA] = this.getUserProfilePDA(followingUser);
+    const [configPDA] = this.getConfigPDA();
+    const [eventAuthorityPDA] = this.getEventAuthorityPDA();
 
     const instruction = new TransactionInstruction({
       keys: [
         { pubkey: actualAccountAddress, isSigner: false, isWritable: true }, // Use actual account address
         { pubkey: followerProfilePDA, isSigner: false, isWritable: true },
         { pubkey: followingProfilePDA, isSigner: false, isWritable: true },
+        { pubkey: configPDA, isSigner: false, isWritable: false }, // config
         { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
         { pubkey: followingUser, isSigner: false, isWritable: false },
+        { pubkey: eventAuthorityPDA, isSigner: false, isWritable: false }, // event_authority
+        { pubkey: this.programId, isSigner: false, isWritable: false }, // program
       ],
       programId: this.programId,
       data: INSTRUCTION_DISCRIMINATORS.unfollowUser,
@@ -2182,15 +2410,13 @@ export class SolcialsCustomProgramService {
       console.log('📋 Including metadata CID in post for public access:', metadataCid);
     }
 
-    const timestamp = Math.floor(Date.now() / 1000); // Convert milliseconds to seconds
-    const [postPDA] = this.getPostPDA(wallet.publicKey, timestamp);
-    const [userProfilePDA] = this.getUserProfilePDA(wallet.publicKey);
-
-    // Ensure user profile exists
+    // Ensure user profile exists, the post PDA is seeded by its post nonce
     await this.ensureUserProfile(wallet);
 
+    const postPDA = await this.getNextPostPDA(wallet.publicKey);
+
     // Create instruction data for image post with cNFT
-    const instructionData = Buffer.alloc(8 + finalContent.length + 4 + 8 + 33);
+    const instructionData = Buffer.alloc(8 + finalContent.length + 4 + 33);
     let offset = 0;
     
     // Instruction discriminator for create_image_post
@@ -2203,12 +2429,6 @@ export class SolcialsCustomProgramService {
     Buffer.from(finalContent, 'utf8').copy(instructionData, offset);
     offset += finalContent.length;
     
-    // Timestamp
-    const timestampBuffer = Buffer.alloc(8);
-    timestampBuffer.writeBigInt64LE(BigInt(timestamp), 0);
-    timestampBuffer.copy(instructionData, offset);
-    offset += 8;
-    
     // Optional reply_to (1 byte flag + 32 bytes if present)
     if (replyTo) {
       instructionData.writeUInt8(1, offset);
@@ -2219,12 +2439,7 @@ export class SolcialsCustomProgramService {
     }
 
     const instruction = new TransactionInstruction({
-      keys: [
-        { pubkey: postPDA, isSigner: false, isWritable: true },
-        { pubkey: userProfilePDA, isSigner: false, isWritable: true },
-        { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // author
-        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
-      ],
+      keys: await this.getCreatePostKeys(wallet.publicKey, postPDA, replyTo),
       programId: this.programId,
       data: instructionData,
     });
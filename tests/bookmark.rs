@@ -0,0 +1,67 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use solcials::Bookmark;
+
+fn bookmark_pda(user: &Pubkey, post: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bookmark", user.as_ref(), post.as_ref()], &solcials::ID).0
+}
+
+fn bookmark_post_ix(user: &Pubkey, post: &Pubkey, folder: Option<u8>) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::BookmarkPost {
+            bookmark: bookmark_pda(user, post),
+            post: *post,
+            config: config_pda(),
+            user: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::BookmarkPost { folder }.data(),
+    }
+}
+
+fn remove_bookmark_ix(user: &Pubkey, post: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::RemoveBookmark {
+            bookmark: bookmark_pda(user, post),
+            post: *post,
+            config: config_pda(),
+            user: *user,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::RemoveBookmark {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn bookmark_leaves_the_post_untouched() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "gm")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+    let before = context.banks_client.get_account(post).await.unwrap().unwrap();
+
+    send(&mut context, &[bookmark_post_ix(&bob.pubkey(), &post, Some(2))], &[&bob]).await.unwrap();
+    let address = bookmark_pda(&bob.pubkey(), &post);
+    let bookmark: Bookmark = fetch(&mut context, address).await;
+    assert_eq!(bookmark.user, bob.pubkey());
+    assert_eq!(bookmark.post, post);
+    assert_eq!(bookmark.folder, Some(2));
+    let after = context.banks_client.get_account(post).await.unwrap().unwrap();
+    assert_eq!(after, before);
+
+    // The bookmark outlives the post and can still be removed
+    send(&mut context, &[delete_post_ix(&alice.pubkey(), &post, &[])], &[&alice]).await.unwrap();
+    send(&mut context, &[remove_bookmark_ix(&bob.pubkey(), &post)], &[&bob]).await.unwrap();
+    assert_eq!(data_len(&mut context, address).await, None);
+}
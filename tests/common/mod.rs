@@ -0,0 +1,292 @@
+// Shared setup for the program-test suites: the program runs natively, Config and Treasury
+// are injected since initialize needs an upgradeable ProgramData account
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, AccountDeserialize, AccountSerialize, InstructionData};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solcials::{
+    Config, SocialError, Treasury, MAX_BIO_LENGTH, MAX_CHUNK_SIZE, MAX_CONTENT_LENGTH,
+    MAX_DISPLAY_NAME_LENGTH, MAX_LOCATION_LENGTH, MAX_URL_LENGTH, MAX_USERNAME_LENGTH,
+};
+
+pub const STARTING_LAMPORTS: u64 = 10_000_000_000;
+
+// Anchor's entry wants accounts that live for 'info, which processor! can't express
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    solcials::entry(program_id, accounts, data)
+}
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("solcials", solcials::ID, processor!(process_instruction));
+    program_test.prefer_bpf(false);
+
+    let config = Config {
+        admin: Pubkey::new_unique(),
+        reserved_usernames: Vec::new(),
+        verifiers: Vec::new(),
+        paused: false,
+        max_content_length: MAX_CONTENT_LENGTH as u16,
+        max_username_length: MAX_USERNAME_LENGTH as u16,
+        max_display_name_length: MAX_DISPLAY_NAME_LENGTH as u16,
+        max_bio_length: MAX_BIO_LENGTH as u16,
+        max_url_length: MAX_URL_LENGTH as u16,
+        max_location_length: MAX_LOCATION_LENGTH as u16,
+        max_chunk_size: MAX_CHUNK_SIZE as u16,
+        text_post_fee_bps: 0,
+        image_post_fee_bps: 0,
+        treasury_authority: Pubkey::new_unique(),
+        verified_fee_discount_bps: 0,
+        token_holder_discount_bps: 0,
+        discount_mint: None,
+        discount_min_balance: 0,
+        fee_exempt_wallets: Vec::new(),
+        bump: Pubkey::find_program_address(&[b"config"], &solcials::ID).1,
+    };
+    add_program_account(&mut program_test, config_pda(), &config, 1024);
+
+    let treasury = Treasury {
+        total_collected: 0,
+        total_withdrawn: 0,
+        bump: Pubkey::find_program_address(&[b"treasury"], &solcials::ID).1,
+    };
+    add_program_account(&mut program_test, treasury_pda(), &treasury, 8 + 8 + 8 + 1);
+
+    program_test
+}
+
+// Inject an account owned by the program, padded to space bytes
+pub fn add_program_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    account: &T,
+    space: usize,
+) {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= space, "account does not fit in {space} bytes");
+    data.resize(space, 0);
+
+    program_test.add_account(
+        address,
+        SolanaAccount {
+            lamports: Rent::default().minimum_balance(space),
+            data,
+            owner: solcials::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn add_wallet(program_test: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    program_test.add_account(
+        wallet.pubkey(),
+        SolanaAccount::new(STARTING_LAMPORTS, 0, &system_program::ID),
+    );
+    wallet
+}
+
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+pub fn assert_anchor_error(result: std::result::Result<(), BanksClientError>, expected: u32) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, expected, "unexpected error code"),
+        other => panic!("expected custom error {expected}, got {other:?}"),
+    }
+}
+
+pub fn assert_social_error(result: std::result::Result<(), BanksClientError>, expected: SocialError) {
+    assert_anchor_error(result, expected.into());
+}
+
+pub async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .expect("account does not exist");
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+pub async fn data_len(context: &mut ProgramTestContext, address: Pubkey) -> Option<usize> {
+    context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .map(|account| account.data.len())
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &solcials::ID).0
+}
+
+pub fn treasury_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury"], &solcials::ID).0
+}
+
+pub fn event_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &solcials::ID).0
+}
+
+pub fn profile_pda(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_profile", user.as_ref()], &solcials::ID).0
+}
+
+pub fn profile_tombstone_pda(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile_tombstone", user.as_ref()], &solcials::ID).0
+}
+
+pub fn follow_pda(follower: &Pubkey, following: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"follow", follower.as_ref(), following.as_ref()], &solcials::ID).0
+}
+
+pub fn block_pda(blocker: &Pubkey, blocked: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"block", blocker.as_ref(), blocked.as_ref()], &solcials::ID).0
+}
+
+pub fn like_pda(user: &Pubkey, post: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"like", user.as_ref(), post.as_ref()], &solcials::ID).0
+}
+
+pub fn post_pda(author: &Pubkey, post_nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"post", author.as_ref(), post_nonce.to_le_bytes().as_ref()],
+        &solcials::ID,
+    )
+    .0
+}
+
+pub fn chunk_pda(post: &Pubkey, chunk_index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"chunk", post.as_ref(), &chunk_index.to_le_bytes()], &solcials::ID).0
+}
+
+pub fn initialize_user_profile_ix(user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::InitializeUserProfile {
+            user_profile: profile_pda(user),
+            profile_tombstone: profile_tombstone_pda(user),
+            config: config_pda(),
+            user: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::InitializeUserProfile {}.data(),
+    }
+}
+
+pub fn follow_user_ix(follower: &Pubkey, following: &Pubkey, following_profile: Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::FollowUser {
+            follow_account: follow_pda(follower, following),
+            follower_profile: profile_pda(follower),
+            following_profile,
+            block_account: block_pda(following, follower),
+            config: config_pda(),
+            follower: *follower,
+            following: *following,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::FollowUser {}.data(),
+    }
+}
+
+pub fn create_text_post_ix(author: &Pubkey, post_nonce: u64, content: &str) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::CreateTextPost {
+            post: post_pda(author, post_nonce),
+            parent_post: None,
+            parent_block_account: None,
+            user_profile: profile_pda(author),
+            config: config_pda(),
+            treasury: treasury_pda(),
+            discount_token_account: None,
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::CreateTextPost {
+            content: content.to_string(),
+            reply_to: None,
+        }
+        .data(),
+    }
+}
+
+pub fn delete_post_ix(author: &Pubkey, post: &Pubkey, remaining: &[Pubkey]) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::DeletePost {
+            post: *post,
+            parent_post: None,
+            tombstone: None,
+            user_profile: profile_pda(author),
+            config: config_pda(),
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None)
+        .into_iter()
+        .chain(remaining.iter().map(|address| AccountMeta::new(*address, false)))
+        .collect(),
+        data: solcials::instruction::DeletePost {}.data(),
+    }
+}
+
+pub fn close_post_accounts_ix(author: &Pubkey, post: &Pubkey, accounts: &[Pubkey]) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::ClosePostAccounts {
+            post: *post,
+            config: config_pda(),
+            author: *author,
+        }
+        .to_account_metas(None)
+        .into_iter()
+        .chain(accounts.iter().map(|address| AccountMeta::new(*address, false)))
+        .collect(),
+        data: solcials::instruction::ClosePostAccounts {}.data(),
+    }
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use solcials::{FollowRelation, SocialError, UserProfile};
+
+#[tokio::test]
+async fn follow_updates_both_profiles() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+
+    let ix = follow_user_ix(&alice.pubkey(), &bob.pubkey(), profile_pda(&bob.pubkey()));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+
+    let alice_profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    let bob_profile: UserProfile = fetch(&mut context, profile_pda(&bob.pubkey())).await;
+    assert_eq!(alice_profile.following_count, 1);
+    assert_eq!(bob_profile.followers_count, 1);
+}
+
+#[tokio::test]
+async fn self_follow_is_rejected() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+
+    let ix = follow_user_ix(&alice.pubkey(), &alice.pubkey(), profile_pda(&alice.pubkey()));
+    let result = send(&mut context, &[ix], &[&alice]).await;
+    assert_social_error(result, SocialError::CannotFollowSelf);
+
+    let alice_profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    assert_eq!(alice_profile.following_count, 0);
+    assert_eq!(alice_profile.followers_count, 0);
+}
+
+#[tokio::test]
+async fn another_wallets_profile_is_rejected() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let carol = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    for user in [&alice, &bob, &carol] {
+        send(&mut context, &[initialize_user_profile_ix(&user.pubkey())], &[user]).await.unwrap();
+    }
+
+    // Following bob while passing carol's profile fails the profile's seeds check
+    let ix = follow_user_ix(&alice.pubkey(), &bob.pubkey(), profile_pda(&carol.pubkey()));
+    let result = send(&mut context, &[ix], &[&alice]).await;
+    assert_anchor_error(result, ErrorCode::ConstraintSeeds.into());
+
+    let carol_profile: UserProfile = fetch(&mut context, profile_pda(&carol.pubkey())).await;
+    assert_eq!(carol_profile.followers_count, 0);
+}
+
+#[tokio::test]
+async fn profile_recording_another_wallet_is_rejected() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+
+    // A profile at bob's address whose user field names someone else
+    let (bob_profile_pda, bump) =
+        Pubkey::find_program_address(&[b"user_profile", bob.pubkey().as_ref()], &solcials::ID);
+    let mismatched = UserProfile {
+        user: Pubkey::new_unique(),
+        username: None,
+        display_name: None,
+        bio: None,
+        avatar_url: None,
+        cover_image_url: None,
+        website_url: None,
+        location: None,
+        followers_count: 0,
+        following_count: 0,
+        post_count: 0,
+        created_at: 0,
+        verified: false,
+        bump,
+        post_nonce: 0,
+        verified_by: None,
+        verified_at: None,
+        revocation_reason: None,
+        private: false,
+        pinned_posts: Vec::new(),
+    };
+    add_program_account(&mut program_test, bob_profile_pda, &mismatched, 1024);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+
+    let ix = follow_user_ix(&alice.pubkey(), &bob.pubkey(), bob_profile_pda);
+    let result = send(&mut context, &[ix], &[&alice]).await;
+    assert_social_error(result, SocialError::FollowingProfileMismatch);
+}
+
+fn unfollow_user_ix(follower: &Pubkey, following: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::UnfollowUser {
+            follow_account: follow_pda(follower, following),
+            follower_profile: profile_pda(follower),
+            following_profile: profile_pda(following),
+            config: config_pda(),
+            follower: *follower,
+            following: *following,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::UnfollowUser {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn unfollow_decrements_both_profiles() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    let ix = follow_user_ix(&alice.pubkey(), &bob.pubkey(), profile_pda(&bob.pubkey()));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+
+    send(&mut context, &[unfollow_user_ix(&alice.pubkey(), &bob.pubkey())], &[&alice]).await.unwrap();
+
+    let alice_profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    let bob_profile: UserProfile = fetch(&mut context, profile_pda(&bob.pubkey())).await;
+    assert_eq!(alice_profile.following_count, 0);
+    assert_eq!(bob_profile.followers_count, 0);
+    assert_eq!(data_len(&mut context, follow_pda(&alice.pubkey(), &bob.pubkey())).await, None);
+}
+
+#[tokio::test]
+async fn unfollow_with_uncounted_follow_underflows() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+
+    // A follow that never went through follow_user, so neither profile counts it
+    let (follow_address, bump) = Pubkey::find_program_address(
+        &[b"follow", alice.pubkey().as_ref(), bob.pubkey().as_ref()],
+        &solcials::ID,
+    );
+    let follow = FollowRelation {
+        follower: alice.pubkey(),
+        following: bob.pubkey(),
+        timestamp: 0,
+        bump,
+    };
+    add_program_account(&mut program_test, follow_address, &follow, 8 + 32 + 32 + 8 + 1);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+
+    let result = send(&mut context, &[unfollow_user_ix(&alice.pubkey(), &bob.pubkey())], &[&alice]).await;
+    assert_social_error(result, SocialError::CounterUnderflow);
+    assert!(data_len(&mut context, follow_address).await.is_some());
+}
+
+fn block_user_ix(blocker: &Pubkey, blocked: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::BlockUser {
+            block_account: block_pda(blocker, blocked),
+            blocker_profile: profile_pda(blocker),
+            blocked_profile: profile_pda(blocked),
+            blocker_follow: follow_pda(blocker, blocked),
+            blocked_follow: follow_pda(blocked, blocker),
+            config: config_pda(),
+            blocker: *blocker,
+            blocked: *blocked,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::BlockUser {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn block_removes_follows_both_ways() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    let ix = follow_user_ix(&alice.pubkey(), &bob.pubkey(), profile_pda(&bob.pubkey()));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+    let ix = follow_user_ix(&bob.pubkey(), &alice.pubkey(), profile_pda(&alice.pubkey()));
+    send(&mut context, &[ix], &[&bob]).await.unwrap();
+
+    send(&mut context, &[block_user_ix(&alice.pubkey(), &bob.pubkey())], &[&alice]).await.unwrap();
+
+    let alice_profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    let bob_profile: UserProfile = fetch(&mut context, profile_pda(&bob.pubkey())).await;
+    assert_eq!((alice_profile.following_count, alice_profile.followers_count), (0, 0));
+    assert_eq!((bob_profile.following_count, bob_profile.followers_count), (0, 0));
+    assert_eq!(data_len(&mut context, follow_pda(&alice.pubkey(), &bob.pubkey())).await, None);
+    assert_eq!(data_len(&mut context, follow_pda(&bob.pubkey(), &alice.pubkey())).await, None);
+}
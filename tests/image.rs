@@ -0,0 +1,143 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use solcials::{ImageChunk, Post, SocialError, MAX_IMAGE_CHUNKS};
+
+fn create_image_post_ix(author: &Pubkey, post_nonce: u64, content: &str) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::CreateImagePost {
+            post: post_pda(author, post_nonce),
+            parent_post: None,
+            parent_block_account: None,
+            user_profile: profile_pda(author),
+            config: config_pda(),
+            treasury: treasury_pda(),
+            discount_token_account: None,
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::CreateImagePost {
+            content: content.to_string(),
+            reply_to: None,
+        }
+        .data(),
+    }
+}
+
+fn add_image_chunk_ix(
+    author: &Pubkey,
+    post: &Pubkey,
+    chunk_data: Vec<u8>,
+    chunk_index: u8,
+    total_chunks: u8,
+) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::AddImageChunk {
+            image_chunk: chunk_pda(post, chunk_index),
+            post: *post,
+            config: config_pda(),
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::AddImageChunk {
+            chunk_data,
+            chunk_index,
+            total_chunks,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn multi_chunk_upload_grows_the_post() {
+    const TOTAL_CHUNKS: u8 = 3;
+
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_image_post_ix(&alice.pubkey(), 0, "sunset")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+    let initial_len = data_len(&mut context, post).await.unwrap();
+
+    let mut expected_chunks = Vec::new();
+    for chunk_index in 0..TOTAL_CHUNKS {
+        let chunk_data = vec![chunk_index; 512];
+        let ix = add_image_chunk_ix(&alice.pubkey(), &post, chunk_data.clone(), chunk_index, TOTAL_CHUNKS);
+        send(&mut context, &[ix], &[&alice]).await.unwrap();
+        expected_chunks.push(chunk_pda(&post, chunk_index));
+
+        // Each chunk adds one 32-byte image_chunks entry
+        assert_eq!(
+            data_len(&mut context, post).await.unwrap(),
+            initial_len + 32 * expected_chunks.len()
+        );
+        let stored: Post = fetch(&mut context, post).await;
+        assert_eq!(stored.image_chunks, expected_chunks);
+        assert_eq!(stored.total_image_chunks, TOTAL_CHUNKS);
+
+        let chunk: ImageChunk = fetch(&mut context, chunk_pda(&post, chunk_index)).await;
+        assert_eq!(chunk.post, post);
+        assert_eq!(chunk.data, chunk_data);
+    }
+}
+
+#[tokio::test]
+async fn chunks_closed_in_batches_are_not_needed_by_delete() {
+    const TOTAL_CHUNKS: u8 = 3;
+
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_image_post_ix(&alice.pubkey(), 0, "sunset")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+    for chunk_index in 0..TOTAL_CHUNKS {
+        let ix = add_image_chunk_ix(&alice.pubkey(), &post, vec![chunk_index; 512], chunk_index, TOTAL_CHUNKS);
+        send(&mut context, &[ix], &[&alice]).await.unwrap();
+    }
+
+    let batch = [chunk_pda(&post, 0), chunk_pda(&post, 2)];
+    send(&mut context, &[close_post_accounts_ix(&alice.pubkey(), &post, &batch)], &[&alice]).await.unwrap();
+    let stored: Post = fetch(&mut context, post).await;
+    assert_eq!(stored.image_chunks, vec![chunk_pda(&post, 1)]);
+
+    send(&mut context, &[delete_post_ix(&alice.pubkey(), &post, &[chunk_pda(&post, 1)])], &[&alice])
+        .await
+        .unwrap();
+    for chunk_index in 0..TOTAL_CHUNKS {
+        assert_eq!(data_len(&mut context, chunk_pda(&post, chunk_index)).await, None);
+    }
+}
+
+
+#[tokio::test]
+async fn more_chunks_than_finalize_accepts_are_rejected() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_image_post_ix(&alice.pubkey(), 0, "big")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+
+    let ix = add_image_chunk_ix(&alice.pubkey(), &post, vec![1; 16], 0, MAX_IMAGE_CHUNKS + 1);
+    let result = send(&mut context, &[ix], &[&alice]).await;
+    assert_social_error(result, SocialError::TooManyChunks);
+
+    let ix = add_image_chunk_ix(&alice.pubkey(), &post, vec![1; 16], 0, MAX_IMAGE_CHUNKS);
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+}
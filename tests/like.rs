@@ -0,0 +1,136 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use solcials::{LikeRelation, Post, SocialError, UserProfile};
+
+fn like_post_ix(user: &Pubkey, post: &Pubkey, post_author: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::LikePost {
+            like_account: like_pda(user, post),
+            post: *post,
+            block_account: block_pda(post_author, user),
+            config: config_pda(),
+            user: *user,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::LikePost {}.data(),
+    }
+}
+
+fn unlike_post_ix(user: &Pubkey, post: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::UnlikePost {
+            like_account: like_pda(user, post),
+            post: *post,
+            config: config_pda(),
+            user: *user,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::UnlikePost {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn unlike_decrements_likes() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "gm")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+
+    send(&mut context, &[like_post_ix(&bob.pubkey(), &post, &alice.pubkey())], &[&bob]).await.unwrap();
+    let liked: Post = fetch(&mut context, post).await;
+    assert_eq!(liked.likes, 1);
+
+    send(&mut context, &[unlike_post_ix(&bob.pubkey(), &post)], &[&bob]).await.unwrap();
+    let unliked: Post = fetch(&mut context, post).await;
+    assert_eq!(unliked.likes, 0);
+    assert_eq!(data_len(&mut context, like_pda(&bob.pubkey(), &post)).await, None);
+}
+
+#[tokio::test]
+async fn unlike_with_uncounted_like_underflows() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+
+    // A like on alice's first post that never went through like_post
+    let post = post_pda(&alice.pubkey(), 0);
+    let (like_address, bump) =
+        Pubkey::find_program_address(&[b"like", bob.pubkey().as_ref(), post.as_ref()], &solcials::ID);
+    let like = LikeRelation {
+        user: bob.pubkey(),
+        post,
+        timestamp: 0,
+        bump,
+    };
+    add_program_account(&mut program_test, like_address, &like, 8 + 32 + 32 + 8 + 1);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "gm")], &[&alice]).await.unwrap();
+
+    let result = send(&mut context, &[unlike_post_ix(&bob.pubkey(), &post)], &[&bob]).await;
+    assert_social_error(result, SocialError::CounterUnderflow);
+    assert!(data_len(&mut context, like_address).await.is_some());
+}
+
+fn reclaim_accounts_ix(user: &Pubkey, pairs: &[(Pubkey, Pubkey)]) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::ReclaimAccounts {
+            user_profile: profile_pda(user),
+            config: config_pda(),
+            user: *user,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None)
+        .into_iter()
+        .chain(pairs.iter().flat_map(|(account, counterpart)| {
+            [AccountMeta::new(*account, false), AccountMeta::new(*counterpart, false)]
+        }))
+        .collect(),
+        data: solcials::instruction::ReclaimAccounts {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn reclaim_closes_likes_and_follows() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "gm")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+    send(&mut context, &[like_post_ix(&bob.pubkey(), &post, &alice.pubkey())], &[&bob]).await.unwrap();
+    let ix = follow_user_ix(&bob.pubkey(), &alice.pubkey(), profile_pda(&alice.pubkey()));
+    send(&mut context, &[ix], &[&bob]).await.unwrap();
+
+    let like = like_pda(&bob.pubkey(), &post);
+    let follow = follow_pda(&bob.pubkey(), &alice.pubkey());
+    let ix = reclaim_accounts_ix(&bob.pubkey(), &[(like, post), (follow, profile_pda(&alice.pubkey()))]);
+    send(&mut context, &[ix], &[&bob]).await.unwrap();
+
+    assert_eq!(fetch::<Post>(&mut context, post).await.likes, 0);
+    assert_eq!(fetch::<UserProfile>(&mut context, profile_pda(&alice.pubkey())).await.followers_count, 0);
+    assert_eq!(fetch::<UserProfile>(&mut context, profile_pda(&bob.pubkey())).await.following_count, 0);
+    assert_eq!(data_len(&mut context, like).await, None);
+    assert_eq!(data_len(&mut context, follow).await, None);
+}
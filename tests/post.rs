@@ -0,0 +1,218 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, Discriminator, InstructionData};
+use common::*;
+use solana_sdk::{account::Account as SolanaAccount, instruction::Instruction, signature::Signer};
+use solcials::{LegacyPost, Post, SocialError, LEGACY_POST_SIZE};
+
+fn revision_pda(post: &Pubkey, revision: u16) -> Pubkey {
+    Pubkey::find_program_address(&[b"revision", post.as_ref(), &revision.to_le_bytes()], &solcials::ID).0
+}
+
+fn edit_post_ix(author: &Pubkey, post: &Pubkey, edit_count: u16, content: &str) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::EditPost {
+            revision: revision_pda(post, edit_count),
+            post: *post,
+            config: config_pda(),
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::EditPost {
+            content: content.to_string(),
+        }
+        .data(),
+    }
+}
+
+fn migrate_post_ix(payer: &Pubkey, post: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::MigratePost {
+            post: *post,
+            config: config_pda(),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::MigratePost {}.data(),
+    }
+}
+
+fn create_reply_ix(author: &Pubkey, post_nonce: u64, parent_author: &Pubkey, parent: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::CreateTextPost {
+            post: post_pda(author, post_nonce),
+            parent_post: Some(*parent),
+            parent_block_account: Some(block_pda(parent_author, author)),
+            user_profile: profile_pda(author),
+            config: config_pda(),
+            treasury: treasury_pda(),
+            discount_token_account: None,
+            author: *author,
+            system_program: system_program::ID,
+            event_authority: event_authority_pda(),
+            program: solcials::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::CreateTextPost {
+            content: "reply".to_string(),
+            reply_to: Some(*parent),
+        }
+        .data(),
+    }
+}
+
+fn delete_reply_ix(author: &Pubkey, post: &Pubkey, parent: &Pubkey) -> Instruction {
+    let mut ix = delete_post_ix(author, post, &[]);
+    ix.accounts[1] = AccountMeta::new(*parent, false);
+    ix
+}
+
+#[tokio::test]
+async fn revisions_closed_in_batches_are_not_needed_by_delete() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "v0")], &[&alice]).await.unwrap();
+    let post = post_pda(&alice.pubkey(), 0);
+    for edit_count in 0..3u16 {
+        let ix = edit_post_ix(&alice.pubkey(), &post, edit_count, &format!("v{}", edit_count + 1));
+        send(&mut context, &[ix], &[&alice]).await.unwrap();
+    }
+
+    let batch = [revision_pda(&post, 0), revision_pda(&post, 2)];
+    send(&mut context, &[close_post_accounts_ix(&alice.pubkey(), &post, &batch)], &[&alice]).await.unwrap();
+    let stored: Post = fetch(&mut context, post).await;
+    assert_eq!(stored.edit_count, 3);
+    assert_eq!(stored.open_revisions, 1);
+    for revision in batch {
+        assert_eq!(data_len(&mut context, revision).await, None);
+    }
+
+    // delete_post wants exactly the revisions still open
+    let result = send(&mut context, &[delete_post_ix(&alice.pubkey(), &post, &[])], &[&alice]).await;
+    assert_social_error(result, SocialError::IncompletePostAccounts);
+
+    let remaining = [revision_pda(&post, 1)];
+    send(&mut context, &[delete_post_ix(&alice.pubkey(), &post, &remaining)], &[&alice]).await.unwrap();
+    assert_eq!(data_len(&mut context, post).await, None);
+    assert_eq!(data_len(&mut context, remaining[0]).await, None);
+}
+
+#[tokio::test]
+async fn revision_of_another_post_is_rejected() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "first")], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 1, "second")], &[&alice]).await.unwrap();
+    let first = post_pda(&alice.pubkey(), 0);
+    let second = post_pda(&alice.pubkey(), 1);
+    send(&mut context, &[edit_post_ix(&alice.pubkey(), &second, 0, "edited")], &[&alice]).await.unwrap();
+
+    let ix = close_post_accounts_ix(&alice.pubkey(), &first, &[revision_pda(&second, 0)]);
+    let result = send(&mut context, &[ix], &[&alice]).await;
+    assert_social_error(result, SocialError::InvalidRevision);
+}
+
+
+#[tokio::test]
+async fn legacy_post_is_migrated() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+
+    // Written the way the timestamp-seeded program laid posts out
+    let timestamp: i64 = 1_700_000_000;
+    let (post, bump) = Pubkey::find_program_address(
+        &[b"post", alice.pubkey().as_ref(), &timestamp.to_le_bytes()],
+        &solcials::ID,
+    );
+    let parent = Pubkey::new_unique();
+    let legacy = LegacyPost {
+        author: alice.pubkey(),
+        content: "gm from before versioning".to_string(),
+        post_type: 0,
+        image_chunks: Vec::new(),
+        total_image_chunks: 0,
+        reply_to: Some(parent),
+        timestamp,
+        likes: 3,
+        reposts: 1,
+        replies: 2,
+        bump,
+    };
+    let mut data = Post::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(LEGACY_POST_SIZE, 0);
+    program_test.add_account(
+        post,
+        SolanaAccount {
+            lamports: Rent::default().minimum_balance(LEGACY_POST_SIZE),
+            data,
+            owner: solcials::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // Anyone can pay for the migration
+    send(&mut context, &[migrate_post_ix(&bob.pubkey(), &post)], &[&bob]).await.unwrap();
+    let stored: Post = fetch(&mut context, post).await;
+    assert_eq!(stored.version, 1);
+    assert_eq!(stored.author, alice.pubkey());
+    assert_eq!(stored.root, parent);
+    assert_eq!(stored.content, "gm from before versioning");
+    assert_eq!(stored.reply_to, Some(parent));
+    assert_eq!(stored.timestamp, timestamp);
+    assert_eq!((stored.likes, stored.reposts, stored.replies), (3, 1, 2));
+    assert_eq!(stored.reactions[0], 3);
+    assert_eq!(stored.bump, bump);
+    assert!(data_len(&mut context, post).await.unwrap() > LEGACY_POST_SIZE);
+
+    // The migrated post works with the current instructions and cannot be migrated again
+    send(&mut context, &[edit_post_ix(&alice.pubkey(), &post, 0, "edited")], &[&alice]).await.unwrap();
+    let result = send(&mut context, &[migrate_post_ix(&alice.pubkey(), &post)], &[&alice]).await;
+    assert_social_error(result, SocialError::PostNotLegacy);
+}
+
+
+#[tokio::test]
+async fn deleting_a_reply_decrements_the_parent() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "parent")], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 1, "other")], &[&alice]).await.unwrap();
+    let parent = post_pda(&alice.pubkey(), 0);
+    let other = post_pda(&alice.pubkey(), 1);
+
+    let ix = create_reply_ix(&bob.pubkey(), 0, &alice.pubkey(), &parent);
+    send(&mut context, &[ix], &[&bob]).await.unwrap();
+    let reply = post_pda(&bob.pubkey(), 0);
+    assert_eq!(fetch::<Post>(&mut context, parent).await.replies, 1);
+
+    // Only the post the reply points at is accepted
+    let result = send(&mut context, &[delete_reply_ix(&bob.pubkey(), &reply, &other)], &[&bob]).await;
+    assert_social_error(result, SocialError::InvalidReplyParent);
+
+    send(&mut context, &[delete_reply_ix(&bob.pubkey(), &reply, &parent)], &[&bob]).await.unwrap();
+    assert_eq!(fetch::<Post>(&mut context, parent).await.replies, 0);
+    assert_eq!(data_len(&mut context, reply).await, None);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use anchor_lang::Discriminator;
+use solana_sdk::{account::Account as SolanaAccount, instruction::Instruction, signature::Signer};
+use solcials::{LegacyUserProfile, ProfileTombstone, SocialError, UserProfile, LEGACY_USER_PROFILE_SIZE};
+
+fn close_user_profile_ix(user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::CloseUserProfile {
+            user_profile: profile_pda(user),
+            profile_tombstone: profile_tombstone_pda(user),
+            config: config_pda(),
+            user: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::CloseUserProfile {}.data(),
+    }
+}
+
+fn migrate_user_profile_ix(payer: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::MigrateUserProfile {
+            user_profile: profile_pda(user),
+            config: config_pda(),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::MigrateUserProfile {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn recreated_profile_continues_the_post_nonce() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "first")], &[&alice]).await.unwrap();
+    let first_post = post_pda(&alice.pubkey(), 0);
+    send(&mut context, &[delete_post_ix(&alice.pubkey(), &first_post, &[])], &[&alice]).await.unwrap();
+
+    send(&mut context, &[close_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    assert_eq!(data_len(&mut context, profile_pda(&alice.pubkey())).await, None);
+    let tombstone: ProfileTombstone = fetch(&mut context, profile_tombstone_pda(&alice.pubkey())).await;
+    assert_eq!(tombstone.post_nonce, 1);
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    let profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    assert_eq!(profile.post_nonce, 1);
+    assert_eq!(data_len(&mut context, profile_tombstone_pda(&alice.pubkey())).await, None);
+
+    // The next post lands on a fresh address, not the deleted one
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 1, "second")], &[&alice]).await.unwrap();
+    assert!(data_len(&mut context, post_pda(&alice.pubkey(), 1)).await.is_some());
+    assert_eq!(data_len(&mut context, first_post).await, None);
+}
+
+#[tokio::test]
+async fn profile_with_followers_cannot_close() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    let ix = follow_user_ix(&bob.pubkey(), &alice.pubkey(), profile_pda(&alice.pubkey()));
+    send(&mut context, &[ix], &[&bob]).await.unwrap();
+
+    let result = send(&mut context, &[close_user_profile_ix(&alice.pubkey())], &[&alice]).await;
+    assert_social_error(result, SocialError::OutstandingFollowers);
+}
+
+
+#[tokio::test]
+async fn legacy_profile_is_migrated() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+
+    // Written the way the original program laid profiles out, with a long bio that was later
+    // shortened so stale bytes follow bump
+    let (profile, bump) =
+        Pubkey::find_program_address(&[b"user_profile", alice.pubkey().as_ref()], &solcials::ID);
+    let mut legacy = LegacyUserProfile {
+        user: alice.pubkey(),
+        username: Some("alice".to_string()),
+        display_name: Some("Alice".to_string()),
+        bio: Some("b".repeat(160)),
+        avatar_url: None,
+        cover_image_url: None,
+        website_url: Some("https://example.com".to_string()),
+        location: None,
+        followers_count: 4,
+        following_count: 2,
+        post_count: 7,
+        created_at: 1_700_000_000,
+        verified: true,
+        bump,
+    };
+    let mut data = UserProfile::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    legacy.bio = Some("short".to_string());
+    let mut shortened = UserProfile::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut shortened).unwrap();
+    data[..shortened.len()].copy_from_slice(&shortened);
+    data.resize(LEGACY_USER_PROFILE_SIZE, 0xff);
+    program_test.add_account(
+        profile,
+        SolanaAccount {
+            lamports: Rent::default().minimum_balance(LEGACY_USER_PROFILE_SIZE),
+            data,
+            owner: solcials::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // Anyone can pay for the migration
+    send(&mut context, &[migrate_user_profile_ix(&bob.pubkey(), &alice.pubkey())], &[&bob]).await.unwrap();
+    let stored: UserProfile = fetch(&mut context, profile).await;
+    assert_eq!(stored.user, alice.pubkey());
+    assert_eq!(stored.username.as_deref(), Some("alice"));
+    assert_eq!(stored.bio.as_deref(), Some("short"));
+    assert_eq!(stored.website_url.as_deref(), Some("https://example.com"));
+    assert_eq!((stored.followers_count, stored.following_count, stored.post_count), (4, 2, 7));
+    assert!(stored.verified);
+    assert_eq!(stored.bump, bump);
+    assert_eq!(stored.post_nonce, 0);
+    assert_eq!(stored.verified_by, None);
+    assert!(!stored.private);
+    assert!(stored.pinned_posts.is_empty());
+
+    // The migrated profile works with the current instructions and cannot be migrated again
+    send(&mut context, &[create_text_post_ix(&alice.pubkey(), 0, "gm")], &[&alice]).await.unwrap();
+    assert_eq!(fetch::<UserProfile>(&mut context, profile).await.post_count, 8);
+    let result = send(&mut context, &[migrate_user_profile_ix(&alice.pubkey(), &alice.pubkey())], &[&alice]).await;
+    assert_social_error(result, SocialError::ProfileNotLegacy);
+}
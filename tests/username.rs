@@ -0,0 +1,174 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+use anchor_lang::{system_program, InstructionData};
+use common::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use solcials::{SocialError, UserProfile, UsernameRecord};
+
+fn username_pda(username: &str) -> Pubkey {
+    let seed = hash::hash(username.to_lowercase().as_bytes()).to_bytes();
+    Pubkey::find_program_address(&[b"username", seed.as_ref()], &solcials::ID).0
+}
+
+fn claim_username_ix(user: &Pubkey, username: &str, current_username: Option<&str>) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::ClaimUsername {
+            username_record: username_pda(username),
+            config: config_pda(),
+            current_username_record: current_username.map(username_pda),
+            user_profile: profile_pda(user),
+            user: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::ClaimUsername {
+            username: username.to_string(),
+        }
+        .data(),
+    }
+}
+
+fn release_username_ix(user: &Pubkey, username: &str) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::ReleaseUsername {
+            username_record: username_pda(username),
+            user_profile: profile_pda(user),
+            config: config_pda(),
+            user: *user,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::ReleaseUsername {}.data(),
+    }
+}
+
+fn transfer_username_ix(owner: &Pubkey, new_owner: &Pubkey, username: &str) -> Instruction {
+    Instruction {
+        program_id: solcials::ID,
+        accounts: solcials::accounts::TransferUsername {
+            username_record: username_pda(username),
+            from_profile: profile_pda(owner),
+            to_profile: profile_pda(new_owner),
+            config: config_pda(),
+            owner: *owner,
+            new_owner: *new_owner,
+        }
+        .to_account_metas(None),
+        data: solcials::instruction::TransferUsername {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn username_is_claimed_renamed_and_released() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+
+    send(&mut context, &[claim_username_ix(&alice.pubkey(), "Alice", None)], &[&alice]).await.unwrap();
+    let record: UsernameRecord = fetch(&mut context, username_pda("alice")).await;
+    assert_eq!(record.username, "Alice");
+    assert_eq!(record.owner, alice.pubkey());
+    assert_eq!(record.profile, profile_pda(&alice.pubkey()));
+    let profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    assert_eq!(profile.username.as_deref(), Some("Alice"));
+
+    // Names are unique regardless of casing
+    let result = send(&mut context, &[claim_username_ix(&bob.pubkey(), "ALICE", None)], &[&bob]).await;
+    assert_social_error(result, SocialError::UsernameAlreadyClaimed);
+
+    // Changing only the casing keeps the record
+    let ix = claim_username_ix(&alice.pubkey(), "aLiCe", Some("Alice"));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+    let renamed: UsernameRecord = fetch(&mut context, username_pda("alice")).await;
+    assert_eq!(renamed.username, "aLiCe");
+    assert_eq!(renamed.claimed_at, record.claimed_at);
+    let profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    assert_eq!(profile.username.as_deref(), Some("aLiCe"));
+
+    // A new name releases the old record
+    let ix = claim_username_ix(&alice.pubkey(), "alicia", Some("aLiCe"));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+    assert_eq!(data_len(&mut context, username_pda("alice")).await, None);
+    send(&mut context, &[claim_username_ix(&bob.pubkey(), "alice", None)], &[&bob]).await.unwrap();
+
+    send(&mut context, &[release_username_ix(&alice.pubkey(), "alicia")], &[&alice]).await.unwrap();
+    assert_eq!(data_len(&mut context, username_pda("alicia")).await, None);
+    let profile: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    assert_eq!(profile.username, None);
+}
+
+#[tokio::test]
+async fn username_is_transferred() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+    let bob = add_wallet(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[initialize_user_profile_ix(&alice.pubkey())], &[&alice]).await.unwrap();
+    send(&mut context, &[initialize_user_profile_ix(&bob.pubkey())], &[&bob]).await.unwrap();
+    send(&mut context, &[claim_username_ix(&alice.pubkey(), "alice", None)], &[&alice]).await.unwrap();
+
+    let ix = transfer_username_ix(&alice.pubkey(), &bob.pubkey(), "alice");
+    send(&mut context, &[ix], &[&alice, &bob]).await.unwrap();
+    let record: UsernameRecord = fetch(&mut context, username_pda("alice")).await;
+    assert_eq!(record.owner, bob.pubkey());
+    assert_eq!(record.profile, profile_pda(&bob.pubkey()));
+    let from: UserProfile = fetch(&mut context, profile_pda(&alice.pubkey())).await;
+    let to: UserProfile = fetch(&mut context, profile_pda(&bob.pubkey())).await;
+    assert_eq!(from.username, None);
+    assert_eq!(to.username.as_deref(), Some("alice"));
+
+    // Only the new holder can release it
+    let result = send(&mut context, &[release_username_ix(&alice.pubkey(), "alice")], &[&alice]).await;
+    assert_social_error(result, SocialError::UsernameNotOwned);
+    send(&mut context, &[release_username_ix(&bob.pubkey(), "alice")], &[&bob]).await.unwrap();
+}
+
+#[tokio::test]
+async fn legacy_username_is_claimed_by_its_profile() {
+    let mut program_test = program_test();
+    let alice = add_wallet(&mut program_test);
+
+    // Set before the registry existed, so there is no record for it
+    let (profile, bump) =
+        Pubkey::find_program_address(&[b"user_profile", alice.pubkey().as_ref()], &solcials::ID);
+    let legacy = UserProfile {
+        user: alice.pubkey(),
+        username: Some("alice".to_string()),
+        display_name: None,
+        bio: None,
+        avatar_url: None,
+        cover_image_url: None,
+        website_url: None,
+        location: None,
+        followers_count: 0,
+        following_count: 0,
+        post_count: 0,
+        created_at: 1_700_000_000,
+        verified: false,
+        bump,
+        post_nonce: 0,
+        verified_by: None,
+        verified_at: None,
+        revocation_reason: None,
+        private: false,
+        pinned_posts: Vec::new(),
+    };
+    add_program_account(&mut program_test, profile, &legacy, 2048);
+    let mut context = program_test.start_with_context().await;
+
+    let ix = claim_username_ix(&alice.pubkey(), "Alice", Some("alice"));
+    send(&mut context, &[ix], &[&alice]).await.unwrap();
+    let record: UsernameRecord = fetch(&mut context, username_pda("alice")).await;
+    assert_eq!(record.username, "Alice");
+    assert_eq!(record.profile, profile);
+    let stored: UserProfile = fetch(&mut context, profile).await;
+    assert_eq!(stored.username.as_deref(), Some("Alice"));
+}